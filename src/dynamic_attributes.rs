@@ -0,0 +1,93 @@
+use opentelemetry::logs::{AnyValue, LogRecord};
+use opentelemetry::trace::Span as _;
+use opentelemetry::{Array, Context, InstrumentationScope, KeyValue, Value};
+use opentelemetry_sdk::error::OTelSdkResult;
+use opentelemetry_sdk::logs::{LogProcessor, SdkLogRecord};
+use opentelemetry_sdk::trace::{Span, SpanData, SpanProcessor};
+use std::sync::{PoisonError, RwLock};
+use std::time::Duration;
+
+///Attributes that can change while the process runs, stamped on telemetry when it is emitted
+static DYNAMIC_ATTRIBUTES: RwLock<Vec<KeyValue>> = RwLock::new(Vec::new());
+
+///Set or replace a dynamic attribute
+pub(crate) fn set(attribute: KeyValue) {
+    let mut attributes = DYNAMIC_ATTRIBUTES
+        .write()
+        .unwrap_or_else(PoisonError::into_inner);
+    match attributes.iter_mut().find(|kv| kv.key == attribute.key) {
+        Some(existing) => *existing = attribute,
+        None => attributes.push(attribute),
+    }
+}
+
+pub(crate) fn remove(key: &str) {
+    DYNAMIC_ATTRIBUTES
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .retain(|kv| kv.key.as_str() != key);
+}
+
+pub(crate) fn current() -> Vec<KeyValue> {
+    DYNAMIC_ATTRIBUTES
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+///Stamps the current dynamic attributes on spans when they start
+#[derive(Debug)]
+pub(crate) struct DynamicAttributeSpanProcessor;
+
+impl SpanProcessor for DynamicAttributeSpanProcessor {
+    fn on_start(&self, span: &mut Span, _cx: &Context) {
+        for attribute in current() {
+            span.set_attribute(attribute);
+        }
+    }
+
+    fn on_end(&self, _span: SpanData) {}
+
+    fn force_flush(&self) -> OTelSdkResult {
+        Ok(())
+    }
+
+    fn shutdown_with_timeout(&self, _timeout: Duration) -> OTelSdkResult {
+        Ok(())
+    }
+}
+
+///Stamps the current dynamic attributes on log records when they are emitted
+#[derive(Debug)]
+pub(crate) struct DynamicAttributeLogProcessor;
+
+impl LogProcessor for DynamicAttributeLogProcessor {
+    fn emit(&self, data: &mut SdkLogRecord, _instrumentation: &InstrumentationScope) {
+        for attribute in current() {
+            data.add_attribute(attribute.key, any_value(attribute.value));
+        }
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        Ok(())
+    }
+}
+
+///Log records take AnyValue rather than the Value of span and metric attributes
+fn any_value(value: Value) -> AnyValue {
+    match value {
+        Value::Bool(value) => AnyValue::Boolean(value),
+        Value::I64(value) => AnyValue::Int(value),
+        Value::F64(value) => AnyValue::Double(value),
+        Value::String(value) => AnyValue::String(value),
+        Value::Array(Array::Bool(values)) => list(values),
+        Value::Array(Array::I64(values)) => list(values),
+        Value::Array(Array::F64(values)) => list(values),
+        Value::Array(Array::String(values)) => list(values),
+        other => AnyValue::String(other.to_string().into()),
+    }
+}
+
+fn list<T: Into<AnyValue>>(values: Vec<T>) -> AnyValue {
+    AnyValue::ListAny(Box::new(values.into_iter().map(Into::into).collect()))
+}
@@ -11,12 +11,59 @@ pub enum ObservlibError {
     #[error("Failed to shutdown logger provider: {0}")]
     LoggerShutdown(String),
 
+    #[error("Failed to create trace exporter: {0}")]
+    TraceExporterBuild(String),
+
+    #[error("Failed to create metric exporter: {0}")]
+    MetricExporterBuild(String),
+
+    #[error("Failed to create log exporter: {0}")]
+    LogExporterBuild(String),
+
     #[error("Multiple shutdown failures: {0}")]
     MultipleShutdownFailures(String),
 
+    #[error("Failed to shut down replaced exporters: {0}")]
+    ReplacedExporterShutdown(String),
+
+    #[error("Failed to flush telemetry: {0}")]
+    Flush(String),
+
+    #[error("Conflicting configuration: {0}")]
+    ConflictingConfig(String),
+
+    #[error("No endpoint configured: {0}")]
+    MissingEndpoint(String),
+
+    #[error("Invalid endpoint: {0}")]
+    InvalidEndpoint(String),
+
+    #[cfg(feature = "attributes-file")]
+    #[error("Invalid resource attributes file: {0}")]
+    AttributesFile(String),
+
+    #[error("Invalid sampling ratio {0}, expected a value in 0.0..=1.0")]
+    InvalidSamplingRatio(f64),
+
+    #[error("Invalid filter directive: {0}")]
+    InvalidFilter(String),
+
+    #[cfg(feature = "tls")]
+    #[error("Invalid TLS configuration: {0}")]
+    Tls(String),
+
+    #[error("Failed to create http client: {0}")]
+    HttpClient(String),
+
+    #[error("Failed to install global tracing subscriber: {0}")]
+    SubscriberInit(String),
+
     #[error("Shutdown timeout exceeded")]
     ShutdownTimeout,
 
+    #[error("Flush timeout exceeded")]
+    FlushTimeout,
+
     #[cfg(feature = "async")]
     #[error("Task join error: {0}")]
     TaskJoin(#[from] tokio::task::JoinError),
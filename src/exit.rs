@@ -0,0 +1,48 @@
+use crate::Providers;
+use std::ffi::c_int;
+use std::sync::{Mutex, Once, PoisonError};
+
+///Providers to flush when the process exits, cleared once they are shut down
+static EXIT_PROVIDERS: Mutex<Option<Providers>> = Mutex::new(None);
+
+unsafe extern "C" {
+    fn atexit(callback: extern "C" fn()) -> c_int;
+}
+
+///Flush `providers` from an `atexit` handler, which also runs on `std::process::exit`
+pub(crate) fn register(providers: Providers) {
+    *EXIT_PROVIDERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = Some(providers);
+
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| {
+        // SAFETY: atexit only stores the pointer of a function that lives as long as the
+        // process and takes no arguments.
+        if unsafe { atexit(flush_at_exit) } != 0 {
+            tracing::warn!(target: "observlib", "failed to register the exit flush");
+        }
+    });
+}
+
+///Forget the providers registered for the exit flush
+pub(crate) fn unregister() {
+    EXIT_PROVIDERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take();
+}
+
+extern "C" fn flush_at_exit() {
+    // Unwinding out of an extern "C" function aborts, the flush outcome cannot be
+    // reported anyway
+    let _ = std::panic::catch_unwind(|| {
+        let providers = EXIT_PROVIDERS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        if let Some(providers) = providers {
+            let _ = providers.force_flush();
+        }
+    });
+}
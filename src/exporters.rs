@@ -0,0 +1,519 @@
+use crate::builder::SignalConfig;
+use crate::http_client::{EXPORT_TIMEOUT, ExportClient};
+use crate::{ObservlibError, TelemetryBuilder};
+use crate::{logs, metrics, traces};
+use opentelemetry::KeyValue;
+use opentelemetry::logs::AnyValue;
+use opentelemetry::trace::Event;
+use opentelemetry_otlp::{LogExporter, MetricExporter, SpanExporter};
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::error::{OTelSdkError, OTelSdkResult};
+use opentelemetry_sdk::logs::{LogBatch, LogExporter as _, SdkLogRecord};
+use opentelemetry_sdk::metrics::Temporality;
+use opentelemetry_sdk::metrics::data::ResourceMetrics;
+use opentelemetry_sdk::metrics::exporter::PushMetricExporter;
+use opentelemetry_sdk::trace::{SpanData, SpanExporter as _};
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+///Exporter whose inner OTLP exporter can be replaced while its processor keeps running
+///
+/// Clones share the same slot, the processor owns one and `ExporterHandles` keeps another.
+#[derive(Debug)]
+pub(crate) struct SwappableExporter<E> {
+    slot: Arc<Slot<E>>,
+}
+
+#[derive(Debug)]
+struct Slot<E> {
+    current: RwLock<Arc<E>>,
+    resource: RwLock<Option<Resource>>,
+    fallback: Option<StdoutFallback>,
+    max_batch_bytes: Option<usize>,
+    ///Spans, metric streams or log records exported successfully
+    exported: AtomicU64,
+    failed_exports: AtomicU64,
+}
+
+///Writes batches to stdout while exports keep failing
+///
+/// Once `threshold` exports in a row failed, each following batch is formatted before being
+/// sent and printed if its export fails too. The first success stops the fallback.
+#[derive(Debug)]
+struct StdoutFallback {
+    threshold: usize,
+    failures: AtomicUsize,
+}
+
+impl StdoutFallback {
+    fn degraded(&self) -> bool {
+        self.failures.load(Ordering::Relaxed) >= self.threshold
+    }
+
+    fn record(&self, result: &OTelSdkResult, lines: Option<Vec<String>>) {
+        if result.is_ok() {
+            self.failures.store(0, Ordering::Relaxed);
+            return;
+        }
+        if self.failures.fetch_add(1, Ordering::Relaxed) + 1 == self.threshold {
+            tracing::warn!(
+                target: "observlib",
+                failures = self.threshold,
+                "exports keep failing, writing telemetry to stdout until one succeeds"
+            );
+        }
+        if let Some(lines) = lines {
+            let mut stdout = std::io::stdout().lock();
+            for line in lines {
+                let _ = writeln!(stdout, "{line}");
+            }
+        }
+    }
+}
+
+impl<E> Clone for SwappableExporter<E> {
+    fn clone(&self) -> Self {
+        SwappableExporter {
+            slot: self.slot.clone(),
+        }
+    }
+}
+
+impl<E: InnerExporter> SwappableExporter<E> {
+    ///`fallback_threshold` is the number of failed exports in a row after which batches are
+    ///also written to stdout, None to never do so. Batches estimated larger than
+    ///`max_batch_bytes` are exported in several requests.
+    pub(crate) fn new(
+        exporter: E,
+        fallback_threshold: Option<usize>,
+        max_batch_bytes: Option<usize>,
+    ) -> Self {
+        SwappableExporter {
+            slot: Arc::new(Slot {
+                current: RwLock::new(Arc::new(exporter)),
+                resource: RwLock::new(None),
+                fallback: fallback_threshold.map(|threshold| StdoutFallback {
+                    threshold,
+                    failures: AtomicUsize::new(0),
+                }),
+                max_batch_bytes,
+                exported: AtomicU64::new(0),
+                failed_exports: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    ///`items` cut into consecutive chunks whose estimated size stays under the limit
+    fn split<T>(&self, items: Vec<T>, size: impl Fn(&T) -> usize) -> Vec<Vec<T>> {
+        let Some(max_bytes) = self.slot.max_batch_bytes else {
+            return vec![items];
+        };
+        let mut chunks = Vec::new();
+        let mut chunk = Vec::new();
+        let mut chunk_bytes = 0;
+        for item in items {
+            let item_bytes = size(&item);
+            if !chunk.is_empty() && chunk_bytes + item_bytes > max_bytes {
+                chunks.push(std::mem::take(&mut chunk));
+                chunk_bytes = 0;
+            }
+            chunk_bytes += item_bytes;
+            chunk.push(item);
+        }
+        if !chunk.is_empty() {
+            chunks.push(chunk);
+        }
+        chunks
+    }
+
+    ///Lines to print if the coming export fails, only built while exports keep failing
+    fn fallback_lines(&self, lines: impl FnOnce() -> Vec<String>) -> Option<Vec<String>> {
+        let fallback = self.slot.fallback.as_ref()?;
+        fallback.degraded().then(lines)
+    }
+
+    fn record_export(&self, result: &OTelSdkResult, items: usize, lines: Option<Vec<String>>) {
+        match result {
+            Ok(()) => self
+                .slot
+                .exported
+                .fetch_add(items as u64, Ordering::Relaxed),
+            Err(_) => self.slot.failed_exports.fetch_add(1, Ordering::Relaxed),
+        };
+        if let Some(fallback) = &self.slot.fallback {
+            fallback.record(result, lines);
+        }
+    }
+
+    fn current(&self) -> Arc<E> {
+        self.slot
+            .current
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    ///Export through `exporter` from now on, then retire the previous one
+    ///
+    /// An export in flight finishes on the previous exporter, which is flushed and shut down
+    /// once it is done, waiting at most `timeout` for it.
+    fn replace(&self, mut exporter: E, timeout: Duration) -> OTelSdkResult {
+        if let Some(resource) = &*self
+            .slot
+            .resource
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+        {
+            exporter.apply_resource(resource);
+        }
+        let mut previous = std::mem::replace(
+            &mut *self
+                .slot
+                .current
+                .write()
+                .unwrap_or_else(PoisonError::into_inner),
+            Arc::new(exporter),
+        );
+        let deadline = Instant::now() + timeout;
+        loop {
+            match Arc::try_unwrap(previous) {
+                Ok(mut previous) => return previous.retire(timeout),
+                Err(shared) if Instant::now() < deadline => {
+                    previous = shared;
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+                Err(_) => {
+                    return Err(OTelSdkError::InternalFailure(
+                        "replaced exporter still in use by an export".to_string(),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn set_resource(&self, resource: &Resource) {
+        *self
+            .slot
+            .resource
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Some(resource.clone());
+        // Otherwise an export holds the exporter, the stored resource is applied to the
+        // next one.
+        let _ = self.with_exclusive(|exporter| exporter.apply_resource(resource));
+    }
+
+    ///Run `f` on the current exporter, fails when an export still holds it
+    fn with_exclusive<T>(&self, f: impl FnOnce(&mut E) -> T) -> Result<T, OTelSdkError> {
+        let mut current = self
+            .slot
+            .current
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        Arc::get_mut(&mut current).map(f).ok_or_else(|| {
+            OTelSdkError::InternalFailure("exporter still in use by an export".to_string())
+        })
+    }
+}
+
+///OTLP exporters held by a `SwappableExporter`
+pub(crate) trait InnerExporter {
+    ///Take the resource from the processor, only the span and log exporters need it
+    fn apply_resource(&mut self, resource: &Resource);
+
+    ///Flush and shut down an exporter that was replaced
+    fn retire(&mut self, timeout: Duration) -> OTelSdkResult;
+}
+
+impl InnerExporter for SpanExporter {
+    fn apply_resource(&mut self, resource: &Resource) {
+        self.set_resource(resource);
+    }
+
+    fn retire(&mut self, timeout: Duration) -> OTelSdkResult {
+        self.force_flush().and(self.shutdown_with_timeout(timeout))
+    }
+}
+
+impl InnerExporter for LogExporter {
+    fn apply_resource(&mut self, resource: &Resource) {
+        self.set_resource(resource);
+    }
+
+    fn retire(&mut self, timeout: Duration) -> OTelSdkResult {
+        self.shutdown_with_timeout(timeout)
+    }
+}
+
+impl InnerExporter for MetricExporter {
+    fn apply_resource(&mut self, _resource: &Resource) {}
+
+    fn retire(&mut self, timeout: Duration) -> OTelSdkResult {
+        self.force_flush().and(self.shutdown_with_timeout(timeout))
+    }
+}
+
+impl opentelemetry_sdk::trace::SpanExporter for SwappableExporter<SpanExporter> {
+    async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
+        let mut result = Ok(());
+        for chunk in self.split(batch, span_size) {
+            let lines = self.fallback_lines(|| chunk.iter().map(span_line).collect());
+            let items = chunk.len();
+            let chunk_result = self.current().export(chunk).await;
+            self.record_export(&chunk_result, items, lines);
+            if chunk_result.is_err() {
+                result = chunk_result;
+            }
+        }
+        result
+    }
+
+    fn shutdown_with_timeout(&mut self, timeout: Duration) -> OTelSdkResult {
+        self.with_exclusive(|exporter| exporter.shutdown_with_timeout(timeout))?
+    }
+
+    fn force_flush(&mut self) -> OTelSdkResult {
+        self.with_exclusive(|exporter| exporter.force_flush())?
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        SwappableExporter::set_resource(self, resource);
+    }
+}
+
+impl opentelemetry_sdk::logs::LogExporter for SwappableExporter<LogExporter> {
+    async fn export(&self, batch: LogBatch<'_>) -> OTelSdkResult {
+        let mut result = Ok(());
+        for chunk in self.split(batch.iter().collect(), |(record, _)| log_size(record)) {
+            let lines =
+                self.fallback_lines(|| chunk.iter().map(|(record, _)| log_line(record)).collect());
+            let chunk_result = self.current().export(LogBatch::new(&chunk)).await;
+            self.record_export(&chunk_result, chunk.len(), lines);
+            if chunk_result.is_err() {
+                result = chunk_result;
+            }
+        }
+        result
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.current().shutdown_with_timeout(timeout)
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        SwappableExporter::set_resource(self, resource);
+    }
+}
+
+impl PushMetricExporter for SwappableExporter<MetricExporter> {
+    async fn export(&self, metrics: &ResourceMetrics) -> OTelSdkResult {
+        let result = self.current().export(metrics).await;
+        let streams = metrics
+            .scope_metrics()
+            .map(|scope| scope.metrics().count())
+            .sum();
+        self.record_export(&result, streams, None);
+        result
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.current().force_flush()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.current().shutdown_with_timeout(timeout)
+    }
+
+    fn temporality(&self) -> Temporality {
+        self.current().temporality()
+    }
+}
+
+///Fixed part of an encoded span or log record: ids, timestamps, kind, flags and framing
+const RECORD_OVERHEAD_BYTES: usize = 64;
+
+///Rough encoded size of a span, for splitting batches
+fn span_size(span: &SpanData) -> usize {
+    RECORD_OVERHEAD_BYTES
+        + span.name.len()
+        + attributes_size(&span.attributes)
+        + span.events.iter().map(event_size).sum::<usize>()
+        + span
+            .links
+            .iter()
+            .map(|link| RECORD_OVERHEAD_BYTES + attributes_size(&link.attributes))
+            .sum::<usize>()
+}
+
+fn event_size(event: &Event) -> usize {
+    RECORD_OVERHEAD_BYTES + event.name.len() + attributes_size(&event.attributes)
+}
+
+fn attributes_size(attributes: &[KeyValue]) -> usize {
+    attributes.iter().map(attribute_size).sum()
+}
+
+fn attribute_size(attribute: &KeyValue) -> usize {
+    attribute.key.as_str().len() + attribute.value.as_str().len() + 4
+}
+
+///Drop the last events, then the last attributes, of a span estimated larger than
+///`max_bytes` until it fits, with a warning naming it
+///
+/// What is dropped is added to the span's dropped counts, which backends display, so the
+/// span is still exported and marked as incomplete instead of being rejected whole.
+pub(crate) fn fit_span(span: &mut SpanData, max_bytes: usize) {
+    let mut size = span_size(span);
+    if size <= max_bytes {
+        return;
+    }
+    tracing::warn!(
+        target: "observlib",
+        span = %span.name,
+        trace_id = %span.span_context.trace_id(),
+        estimated_bytes = size,
+        max_bytes,
+        "span larger than the max batch size, dropping its last events and attributes"
+    );
+    while size > max_bytes
+        && let Some(event) = span.events.events.pop()
+    {
+        size -= event_size(&event);
+        span.events.dropped_count += 1;
+    }
+    while size > max_bytes
+        && let Some(attribute) = span.attributes.pop()
+    {
+        size -= attribute_size(&attribute);
+        span.dropped_attributes_count += 1;
+    }
+}
+
+///Rough encoded size of a log record, for splitting batches
+fn log_size(record: &SdkLogRecord) -> usize {
+    RECORD_OVERHEAD_BYTES
+        + record.body().map_or(0, |body| any_value_text(body).len())
+        + record
+            .attributes_iter()
+            .map(|(key, value)| key.as_str().len() + any_value_text(value).len() + 4)
+            .sum::<usize>()
+}
+
+fn span_line(span: &SpanData) -> String {
+    let duration = span
+        .end_time
+        .duration_since(span.start_time)
+        .unwrap_or_default();
+    let mut line = format!(
+        "span trace_id={} span_id={} name={:?} duration_ms={:.3} status={:?}",
+        span.span_context.trace_id(),
+        span.span_context.span_id(),
+        span.name,
+        duration.as_secs_f64() * 1000.0,
+        span.status,
+    );
+    for attribute in &span.attributes {
+        let _ = write!(line, " {}={}", attribute.key, attribute.value);
+    }
+    line
+}
+
+fn log_line(record: &SdkLogRecord) -> String {
+    let mut line = format!(
+        "log severity={} target={}",
+        record.severity_text().unwrap_or("UNSPECIFIED"),
+        record.target().map(|target| target.as_ref()).unwrap_or(""),
+    );
+    if let Some(body) = record.body() {
+        let _ = write!(line, " body={}", any_value_text(body));
+    }
+    for (key, value) in record.attributes_iter() {
+        let _ = write!(line, " {key}={}", any_value_text(value));
+    }
+    line
+}
+
+fn any_value_text(value: &AnyValue) -> String {
+    match value {
+        AnyValue::String(text) => format!("{:?}", text.as_str()),
+        other => format!("{other:?}"),
+    }
+}
+
+///Exporters of the OTLP endpoint for each enabled signal, one per export thread
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ExporterHandles {
+    pub(crate) traces: Vec<SwappableExporter<SpanExporter>>,
+    pub(crate) metrics: Vec<SwappableExporter<MetricExporter>>,
+    pub(crate) logs: Vec<SwappableExporter<LogExporter>>,
+}
+
+///Export outcomes since startup, summed over the exporters of each signal
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct ExportTotals {
+    pub(crate) spans: u64,
+    pub(crate) metric_streams: u64,
+    pub(crate) log_records: u64,
+    pub(crate) failed_exports: u64,
+}
+
+impl ExporterHandles {
+    pub(crate) fn totals(&self) -> ExportTotals {
+        fn sum<E>(exporters: &[SwappableExporter<E>], counter: fn(&Slot<E>) -> &AtomicU64) -> u64 {
+            exporters
+                .iter()
+                .map(|exporter| counter(&exporter.slot).load(Ordering::Relaxed))
+                .sum()
+        }
+        ExportTotals {
+            spans: sum(&self.traces, |slot| &slot.exported),
+            metric_streams: sum(&self.metrics, |slot| &slot.exported),
+            log_records: sum(&self.logs, |slot| &slot.exported),
+            failed_exports: sum(&self.traces, |slot| &slot.failed_exports)
+                + sum(&self.metrics, |slot| &slot.failed_exports)
+                + sum(&self.logs, |slot| &slot.failed_exports),
+        }
+    }
+
+    ///Rebuild every exporter from `config`, batches already queued go out through the new ones
+    ///
+    /// Nothing is replaced unless every exporter could be built. The previous exporters are
+    /// flushed and shut down, their failures are reported once every exporter was replaced.
+    pub(crate) fn replace(
+        &self,
+        config: &TelemetryBuilder,
+        client: Option<&ExportClient>,
+    ) -> Result<(), ObservlibError> {
+        let traces = (self.traces.iter())
+            .map(|_| traces::span_exporter(config, client))
+            .collect::<Result<Vec<_>, _>>()?;
+        let metrics = (self.metrics.iter())
+            .map(|_| metrics::metric_exporter(config, client))
+            .collect::<Result<Vec<_>, _>>()?;
+        let logs = (self.logs.iter())
+            .map(|_| logs::log_exporter(config, client))
+            .collect::<Result<Vec<_>, _>>()?;
+        let timeout = |signal: &SignalConfig| signal.timeout.unwrap_or(EXPORT_TIMEOUT);
+        let retire_errors: Vec<String> = (self.traces.iter().zip(traces))
+            .map(|(exporter, new)| exporter.replace(new, timeout(&config.traces)))
+            .chain(
+                (self.metrics.iter().zip(metrics))
+                    .map(|(exporter, new)| exporter.replace(new, timeout(&config.metrics))),
+            )
+            .chain(
+                (self.logs.iter().zip(logs))
+                    .map(|(exporter, new)| exporter.replace(new, timeout(&config.logs))),
+            )
+            .filter_map(Result::err)
+            .map(|e| e.to_string())
+            .collect();
+        if !retire_errors.is_empty() {
+            return Err(ObservlibError::ReplacedExporterShutdown(
+                retire_errors.join("\n"),
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+use tracing::Event;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::time::{FormatTime, SystemTime};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::layer::Filter;
+use tracing_subscriber::{EnvFilter, Layer, Registry, reload};
+
+pub(crate) type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+///Filter of the default stdout layer when RUST_LOG is not set
+const DEFAULT_FILTER: &str = "info,opentelemetry=debug";
+
+///Filter of the default stdout layer: RUST_LOG when set, `info,opentelemetry=debug` otherwise
+pub(crate) fn default_filter() -> String {
+    std::env::var(EnvFilter::DEFAULT_ENV)
+        .ok()
+        .filter(|filter| !filter.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_FILTER.to_string())
+}
+
+///Handle to swap a layer's EnvFilter at runtime
+pub type FilterHandle = reload::Handle<EnvFilter, Registry>;
+
+///Reload handles of every filter installed by observlib, to build admin endpoints on
+///
+/// # Example
+/// ```no_run
+/// # use observlib::TelemetryBuilder;
+/// # use tracing_subscriber::EnvFilter;
+/// let otel = TelemetryBuilder::new("service")
+///     .with_reloadable_filters()
+///     .build()
+///     .unwrap();
+/// let handles = otel.filter_handles().unwrap();
+/// handles.fmt[0].reload(EnvFilter::new("debug")).unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct FilterHandles {
+    ///Filter of the layer bridging events to OTLP logs, absent when logs are disabled
+    pub otel: Option<FilterHandle>,
+    ///Filters of the fmt layers, in registration order
+    pub fmt: Vec<FilterHandle>,
+}
+
+///Destination of a fmt layer
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FmtWriter {
+    #[default]
+    Stdout,
+    Stderr,
+}
+
+///One local log output, several can be registered with different writers and filters
+///
+/// # Example
+/// ```no_run
+/// # use observlib::{FmtLayerConfig, FmtWriter, TelemetryBuilder};
+/// let otel = TelemetryBuilder::new("service")
+///     .with_fmt_layer(FmtLayerConfig::new("debug").with_writer(FmtWriter::Stderr))
+///     .with_fmt_layer(FmtLayerConfig::new("warn"))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct FmtLayerConfig {
+    pub(crate) filter: String,
+    writer: FmtWriter,
+    span_fields: bool,
+    #[cfg(feature = "json")]
+    json: bool,
+}
+
+impl FmtLayerConfig {
+    ///filter is an EnvFilter directive string (example: info,mycrate=debug)
+    pub fn new(filter: impl Into<String>) -> Self {
+        FmtLayerConfig {
+            filter: filter.into(),
+            writer: FmtWriter::default(),
+            span_fields: true,
+            #[cfg(feature = "json")]
+            json: false,
+        }
+    }
+
+    pub fn with_writer(mut self, writer: FmtWriter) -> Self {
+        self.writer = writer;
+        self
+    }
+
+    ///Print only the names of the enclosing spans, not their fields
+    ///
+    /// Keeps lines short when spans carry many fields, exported spans still have them all.
+    /// Json output then leaves out the current span and span list.
+    pub fn without_span_fields(mut self) -> Self {
+        self.span_fields = false;
+        self
+    }
+
+    ///Format events as json lines instead of human readable text
+    #[cfg(feature = "json")]
+    pub fn json(mut self) -> Self {
+        self.json = true;
+        self
+    }
+
+    fn filtered(
+        &self,
+        directives: &[String],
+        reloadable: bool,
+    ) -> (BoxedLayer, Option<FilterHandle>) {
+        let filter = directives
+            .iter()
+            .filter_map(|directive| directive.parse().ok())
+            .fold(EnvFilter::new(&self.filter), EnvFilter::add_directive);
+        if !reloadable {
+            return (self.layer(filter), None);
+        }
+        let (filter, handle) = reload::Layer::new(filter);
+        (self.layer(filter), Some(handle))
+    }
+
+    fn layer<F: Filter<Registry> + Send + Sync + 'static>(&self, filter: F) -> BoxedLayer {
+        let writer = match self.writer {
+            FmtWriter::Stdout => BoxMakeWriter::new(std::io::stdout),
+            FmtWriter::Stderr => BoxMakeWriter::new(std::io::stderr),
+        };
+        let layer = tracing_subscriber::fmt::layer()
+            .with_thread_names(true)
+            .with_writer(writer);
+
+        #[cfg(feature = "json")]
+        if self.json {
+            return layer
+                .json()
+                .with_current_span(self.span_fields)
+                .with_span_list(self.span_fields)
+                .with_filter(filter)
+                .boxed();
+        }
+        if !self.span_fields {
+            return layer
+                .event_format(SpanNamesOnly::default())
+                .with_filter(filter)
+                .boxed();
+        }
+        layer.with_filter(filter).boxed()
+    }
+}
+
+///Text format printing the enclosing spans by name only: `time LEVEL thread outer:inner: target: fields`
+#[derive(Debug, Default)]
+struct SpanNamesOnly {
+    timer: SystemTime,
+}
+
+impl<N> FormatEvent<Registry, N> for SpanNamesOnly
+where
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, Registry, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> std::fmt::Result {
+        let metadata = event.metadata();
+        if self.timer.format_time(&mut writer).is_err() {
+            writer.write_str("<unknown time>")?;
+        }
+        write!(writer, " {:>5} ", metadata.level())?;
+        if let Some(name) = std::thread::current().name() {
+            write!(writer, "{name} ")?;
+        }
+        if let Some(scope) = ctx.event_scope() {
+            for span in scope.from_root() {
+                write!(writer, "{}:", span.name())?;
+            }
+            writer.write_char(' ')?;
+        }
+        write!(writer, "{}: ", metadata.target())?;
+        ctx.format_fields(writer.by_ref(), event)?;
+        writeln!(writer)
+    }
+}
+
+///Fmt layers of the configurations, with `directives` added to each of their filters
+pub(crate) fn fmt_layers(
+    configs: &[FmtLayerConfig],
+    directives: &[String],
+    reloadable: bool,
+) -> (Vec<BoxedLayer>, Vec<FilterHandle>) {
+    // Without configuration, create a new tracing::Fmt layer to print the logs to stdout.
+    // It follows RUST_LOG, or defaults to `info` level and above, and `debug` and above
+    // for logs from OpenTelemetry crates.
+    let default = [FmtLayerConfig::new(default_filter())];
+    let configs = if configs.is_empty() {
+        &default[..]
+    } else {
+        configs
+    };
+
+    let mut layers = Vec::new();
+    let mut handles = Vec::new();
+    for config in configs {
+        let (layer, handle) = config.filtered(directives, reloadable);
+        layers.push(layer);
+        handles.extend(handle);
+    }
+    (layers, handles)
+}
@@ -0,0 +1,70 @@
+use opentelemetry::trace::{Status, TraceContextExt};
+use opentelemetry::{Context, KeyValue};
+use std::ops::RangeInclusive;
+
+///Decides which HTTP response statuses mark a span as failed
+///
+/// Defaults to 5xx only, so that client errors such as a 404 do not count towards server
+/// error rates. Statuses outside the error ranges leave the span status unset.
+///
+/// # Example
+/// ```no_run
+/// use observlib::HttpStatusMapping;
+///
+/// // a proxy where rate limiting is also our failure
+/// let mapping = HttpStatusMapping::default().with_error_range(429..=429);
+/// mapping.record(429);
+/// ```
+#[derive(Clone, Debug)]
+pub struct HttpStatusMapping {
+    error_ranges: Vec<RangeInclusive<u16>>,
+}
+
+impl Default for HttpStatusMapping {
+    fn default() -> Self {
+        HttpStatusMapping {
+            error_ranges: vec![500..=599],
+        }
+    }
+}
+
+impl HttpStatusMapping {
+    ///Mapping where no status is an error, to be completed with `with_error_range`
+    pub fn empty() -> Self {
+        HttpStatusMapping {
+            error_ranges: Vec::new(),
+        }
+    }
+
+    ///Also treat statuses in `range` as errors
+    pub fn with_error_range(mut self, range: RangeInclusive<u16>) -> Self {
+        self.error_ranges.push(range);
+        self
+    }
+
+    pub fn is_error(&self, status: u16) -> bool {
+        self.error_ranges
+            .iter()
+            .any(|range| range.contains(&status))
+    }
+
+    ///Span status matching an HTTP response status
+    pub fn span_status(&self, status: u16) -> Status {
+        if self.is_error(status) {
+            Status::error(format!("HTTP status {status}"))
+        } else {
+            Status::Unset
+        }
+    }
+
+    ///Set `http.response.status_code` and the mapped status on the current span
+    pub fn record(&self, status: u16) {
+        let cx = Context::current();
+        let span = cx.span();
+        span.set_attribute(KeyValue::new(
+            "http.response.status_code",
+            i64::from(status),
+        ));
+        span.set_status(self.span_status(status));
+    }
+}
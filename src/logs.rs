@@ -1,18 +1,139 @@
-use opentelemetry_otlp::WithExportConfig;
+#[cfg(feature = "grpc")]
+use crate::builder::Transport;
+use crate::dynamic_attributes::DynamicAttributeLogProcessor;
+use crate::exporters::{ExporterHandles, SwappableExporter};
+use crate::http_client::{ExportClient, export_headers, signal_url};
+#[cfg(feature = "grpc")]
+use crate::http_client::{collector_url, export_metadata};
+use crate::processors::ShardedLogProcessor;
+use crate::{ObservlibError, TelemetryBuilder};
+use opentelemetry::baggage::BaggageExt;
+use opentelemetry::logs::LogRecord;
+use opentelemetry::{Context, InstrumentationScope, Key};
+#[cfg(feature = "grpc")]
+use opentelemetry_otlp::WithTonicConfig;
 use opentelemetry_otlp::{LogExporter, Protocol};
+use opentelemetry_otlp::{WithExportConfig, WithHttpConfig};
 use opentelemetry_sdk::Resource;
-use opentelemetry_sdk::logs::SdkLoggerProvider;
+use opentelemetry_sdk::error::OTelSdkResult;
+use opentelemetry_sdk::logs::{
+    BatchConfigBuilder, BatchLogProcessor, LogProcessor, SdkLogRecord, SdkLoggerProvider,
+    SimpleLogProcessor,
+};
 
-pub fn init_logs(resource: Resource, endpoint: &str) -> SdkLoggerProvider {
-    let exporter = LogExporter::builder()
+pub fn init_logs(
+    resource: Resource,
+    config: &TelemetryBuilder,
+    client: Option<&ExportClient>,
+    exporters: &mut ExporterHandles,
+) -> Result<SdkLoggerProvider, ObservlibError> {
+    let mut new_exporter = || -> Result<_, ObservlibError> {
+        let exporter = SwappableExporter::new(
+            log_exporter(config, client)?,
+            config.stdout_fallback,
+            config.logs.max_batch_bytes,
+        );
+        exporters.logs.push(exporter.clone());
+        Ok(exporter)
+    };
+
+    // Processors are called in registration order, enrichment has to happen before
+    // the batch processor takes its copy of the record.
+    let mut builder = SdkLoggerProvider::builder().with_log_processor(DynamicAttributeLogProcessor);
+    if !config.baggage_log_keys.is_empty() {
+        builder = builder.with_log_processor(BaggageLogProcessor {
+            keys: config.baggage_log_keys.clone(),
+        });
+    }
+    if config.immediate_log_export {
+        return Ok(builder
+            .with_log_processor(SimpleLogProcessor::new(new_exporter()?))
+            .with_resource(resource)
+            .build());
+    }
+    let batch_config = || {
+        let mut batch_config = BatchConfigBuilder::default();
+        if let Some(max_queue_size) = config.logs.max_queue_size {
+            batch_config = batch_config.with_max_queue_size(max_queue_size);
+        }
+        if let Some(max_export_batch_size) = config.logs.max_export_batch_size {
+            batch_config = batch_config.with_max_export_batch_size(max_export_batch_size);
+        }
+        if let Some(interval) = config.logs.export_interval {
+            batch_config = batch_config.with_scheduled_delay(interval);
+        }
+        batch_config.build()
+    };
+    let shards = (0..config.logs.export_threads.max(1))
+        .map(|_| {
+            Ok(BatchLogProcessor::builder(new_exporter()?)
+                .with_batch_config(batch_config())
+                .build())
+        })
+        .collect::<Result<_, ObservlibError>>()?;
+    let batch = ShardedLogProcessor::new(shards);
+    Ok(builder
+        .with_log_processor(batch)
+        .with_resource(resource)
+        .build())
+}
+
+///OTLP exporter of the configured endpoint
+pub(crate) fn log_exporter(
+    config: &TelemetryBuilder,
+    client: Option<&ExportClient>,
+) -> Result<LogExporter, ObservlibError> {
+    #[cfg(feature = "grpc")]
+    if config.transport == Transport::Grpc {
+        let mut exporter = LogExporter::builder()
+            .with_tonic()
+            .with_endpoint(collector_url(config, &config.logs))
+            .with_metadata(export_metadata(config, &config.logs));
+        if let Some(timeout) = config.logs.timeout {
+            exporter = exporter.with_timeout(timeout);
+        }
+        return exporter
+            .build()
+            .map_err(|e| ObservlibError::LogExporterBuild(e.to_string()));
+    }
+
+    let mut exporter = LogExporter::builder()
         .with_http()
         .with_protocol(Protocol::HttpBinary)
-        .with_endpoint(format!("http://{}/v1/logs", endpoint))
+        .with_endpoint(signal_url(config, &config.logs, "logs"))
+        .with_headers(export_headers(config, &config.logs));
+    if let Some(compression) = config.logs.compression {
+        exporter = exporter.with_compression(compression);
+    }
+    if let Some(timeout) = config.logs.timeout {
+        exporter = exporter.with_timeout(timeout);
+    }
+    if let Some(client) = client {
+        exporter = exporter.with_http_client(client.clone());
+    }
+    exporter
         .build()
-        .expect("Failed to create log exporter");
+        .map_err(|e| ObservlibError::LogExporterBuild(e.to_string()))
+}
 
-    SdkLoggerProvider::builder()
-        .with_batch_exporter(exporter)
-        .with_resource(resource)
-        .build()
+///Copies selected baggage entries of the emitting context onto log records
+#[derive(Debug)]
+struct BaggageLogProcessor {
+    keys: Vec<Key>,
+}
+
+impl LogProcessor for BaggageLogProcessor {
+    fn emit(&self, data: &mut SdkLogRecord, _instrumentation: &InstrumentationScope) {
+        let cx = Context::current();
+        let baggage = cx.baggage();
+        for key in &self.keys {
+            if let Some(value) = baggage.get(key) {
+                data.add_attribute(key.clone(), value.clone());
+            }
+        }
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        Ok(())
+    }
 }
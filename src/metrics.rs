@@ -1,18 +1,440 @@
-use opentelemetry_otlp::WithExportConfig;
+#[cfg(feature = "grpc")]
+use crate::builder::Transport;
+use crate::dynamic_attributes;
+use crate::exporters::{ExporterHandles, SwappableExporter};
+use crate::http_client::{ExportClient, export_headers, signal_url};
+#[cfg(feature = "grpc")]
+use crate::http_client::{collector_url, export_metadata};
+use crate::readers::AlignedReader;
+use crate::scope::default_scope;
+use crate::{ObservlibError, TelemetryBuilder};
+use opentelemetry::metrics::{Counter, Histogram, Meter, ObservableGauge};
+use opentelemetry::trace::{TraceContextExt, TraceId};
+use opentelemetry::{Context, KeyValue, global};
+#[cfg(feature = "grpc")]
+use opentelemetry_otlp::WithTonicConfig;
 use opentelemetry_otlp::{MetricExporter, Protocol};
+use opentelemetry_otlp::{WithExportConfig, WithHttpConfig};
 use opentelemetry_sdk::Resource;
-use opentelemetry_sdk::metrics::SdkMeterProvider;
+use opentelemetry_sdk::metrics::{Instrument, PeriodicReader, SdkMeterProvider, Stream};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, PoisonError, RwLock};
+use std::time::Duration;
 
-pub fn init_metrics(resource: Resource, endpoint: &str) -> SdkMeterProvider {
-    let exporter = MetricExporter::builder()
+///Attributes merged into the data points recorded through observlib's helpers
+static DEFAULT_ATTRIBUTES: RwLock<Vec<KeyValue>> = RwLock::new(Vec::new());
+
+///Prefix of the metric names, applied by `histogram` itself since the SDK drops advisory
+///boundaries once a view, such as the prefix one, matches an instrument
+static METRIC_PREFIX: RwLock<Option<String>> = RwLock::new(None);
+
+///Instruments of observlib's helpers by name, built once per meter provider
+static INSTRUMENTS: LazyLock<Mutex<Instruments>> = LazyLock::new(Mutex::default);
+
+#[derive(Default)]
+struct Instruments {
+    counters: HashMap<Cow<'static, str>, Counter<u64>>,
+    #[cfg(feature = "async")]
+    histograms: HashMap<Cow<'static, str>, Histogram<f64>>,
+}
+
+pub fn init_metrics(
+    resource: Resource,
+    config: &TelemetryBuilder,
+    client: Option<&ExportClient>,
+    exporters: &mut ExporterHandles,
+) -> Result<SdkMeterProvider, ObservlibError> {
+    let exporter = SwappableExporter::new(metric_exporter(config, client)?, None, None);
+    exporters.metrics.push(exporter.clone());
+
+    *DEFAULT_ATTRIBUTES
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = config.metric_attributes.clone();
+    *METRIC_PREFIX
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = config.metric_prefix.clone();
+    // Instruments built so far belong to the previous, possibly no-op, provider.
+    *INSTRUMENTS.lock().unwrap_or_else(PoisonError::into_inner) = Instruments::default();
+
+    let mut builder = match config.aligned_metrics_interval {
+        Some(interval) => {
+            SdkMeterProvider::builder().with_reader(AlignedReader::new(exporter, interval))
+        }
+        None => match config.metrics.export_interval {
+            Some(interval) => SdkMeterProvider::builder().with_reader(
+                PeriodicReader::builder(exporter)
+                    .with_interval(interval)
+                    .build(),
+            ),
+            None => SdkMeterProvider::builder().with_periodic_exporter(exporter),
+        },
+    };
+    if let Some(prefix) = config.metric_prefix.clone() {
+        builder = builder.with_view(move |instrument: &Instrument| {
+            if instrument.name().starts_with(prefix.as_str()) {
+                return None;
+            }
+            Stream::builder()
+                .with_name(format!("{prefix}{}", instrument.name()))
+                .build()
+                .ok()
+        });
+    }
+    Ok(builder.with_resource(resource).build())
+}
+
+///OTLP exporter of the configured endpoint
+pub(crate) fn metric_exporter(
+    config: &TelemetryBuilder,
+    client: Option<&ExportClient>,
+) -> Result<MetricExporter, ObservlibError> {
+    #[cfg(feature = "grpc")]
+    if config.transport == Transport::Grpc {
+        let mut exporter = MetricExporter::builder()
+            .with_tonic()
+            .with_endpoint(collector_url(config, &config.metrics))
+            .with_metadata(export_metadata(config, &config.metrics));
+        if let Some(timeout) = config.metrics.timeout {
+            exporter = exporter.with_timeout(timeout);
+        }
+        return exporter
+            .build()
+            .map_err(|e| ObservlibError::MetricExporterBuild(e.to_string()));
+    }
+
+    let mut exporter = MetricExporter::builder()
         .with_http()
         .with_protocol(Protocol::HttpBinary) //can be changed to `Protocol::HttpJson` to export in JSON format
-        .with_endpoint(format!("http://{}/v1/metrics", endpoint))
+        .with_endpoint(signal_url(config, &config.metrics, "metrics"))
+        .with_headers(export_headers(config, &config.metrics));
+    if let Some(compression) = config.metrics.compression {
+        exporter = exporter.with_compression(compression);
+    }
+    if let Some(timeout) = config.metrics.timeout {
+        exporter = exporter.with_timeout(timeout);
+    }
+    if let Some(client) = client {
+        exporter = exporter.with_http_client(client.clone());
+    }
+    exporter
         .build()
-        .expect("Failed to create metric exporter");
+        .map_err(|e| ObservlibError::MetricExporterBuild(e.to_string()))
+}
 
-    SdkMeterProvider::builder()
-        .with_periodic_exporter(exporter)
-        .with_resource(resource)
+///Merge the default metric attributes configured on the builder with `attributes`
+///
+/// The SDK cannot add attributes to data points at export time, views only filter them, so
+/// instruments created by the application pass their attributes through this function:
+/// ```no_run
+/// # use observlib::{KeyValue, global};
+/// let requests = global::meter("api").u64_counter("requests").build();
+/// requests.add(1, &observlib::metric_attributes(&[KeyValue::new("route", "/users")]));
+/// ```
+/// observlib's own helpers apply the defaults already. Dynamic attributes set through
+/// `OtelManager::set_dynamic_attribute` are merged as well, attributes passed here win over
+/// both when keys collide.
+pub fn metric_attributes(attributes: &[KeyValue]) -> Vec<KeyValue> {
+    let mut merged = DEFAULT_ATTRIBUTES
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    for attribute in dynamic_attributes::current()
+        .into_iter()
+        .chain(attributes.iter().cloned())
+    {
+        match merged.iter_mut().find(|kv| kv.key == attribute.key) {
+            Some(existing) => *existing = attribute,
+            None => merged.push(attribute),
+        }
+    }
+    merged
+}
+
+///Record a histogram value, returning the trace id of the current sampled span if any
+///
+/// No exemplar is attached to the data point, opentelemetry_sdk 0.31 does not produce
+/// them. Log the returned trace id next to outliers to correlate them with their trace.
+pub fn record_with_trace_id<T>(
+    histogram: &Histogram<T>,
+    value: T,
+    attributes: &[KeyValue],
+) -> Option<TraceId> {
+    histogram.record(value, &metric_attributes(attributes));
+
+    let cx = Context::current();
+    let span_context = cx.span().span_context().clone();
+    if span_context.is_valid() && span_context.is_sampled() {
+        Some(span_context.trace_id())
+    } else {
+        None
+    }
+}
+
+///Record `duration` in seconds, the unit OpenTelemetry conventions use for durations
+///
+/// The attributes go through `metric_attributes`.
+///
+/// # Example
+/// ```no_run
+/// # use std::time::Instant;
+/// let job_duration = observlib::histogram("job.duration", &[]);
+/// let started = Instant::now();
+/// observlib::record_duration(&job_duration, started.elapsed(), &[]);
+/// ```
+pub fn record_duration(histogram: &Histogram<f64>, duration: Duration, attributes: &[KeyValue]) {
+    histogram.record(duration.as_secs_f64(), &metric_attributes(attributes));
+}
+
+///Count an event and annotate the current span with it, under the same name and attributes
+///
+/// Keeps the metric and the trace view of one logical event, such as a cache miss, in sync.
+/// The counter goes through `metric_attributes`, the span event gets `attributes` as given.
+///
+/// # Example
+/// ```no_run
+/// # use observlib::KeyValue;
+/// observlib::observe_event("cache.miss", &[KeyValue::new("cache", "users")]);
+/// ```
+pub fn observe_event(name: impl Into<Cow<'static, str>>, attributes: &[KeyValue]) {
+    let name = name.into();
+    cached_counter(name.clone(), None).add(1, &metric_attributes(attributes));
+    Context::current()
+        .span()
+        .add_event(name, attributes.to_vec());
+}
+
+///Count a handled request in `requests_total`, the rate of the RED method
+///
+/// Pass the same attributes, such as route and method, to `record_error_metric` for failed
+/// requests so error ratios can be computed per series. Both go through `metric_attributes`.
+///
+/// # Example
+/// ```no_run
+/// # use observlib::KeyValue;
+/// let route = [KeyValue::new("http.route", "/users")];
+/// observlib::record_request(&route);
+/// # let failed = false;
+/// if failed {
+///     observlib::record_error_metric(&route);
+/// }
+/// ```
+pub fn record_request(attributes: &[KeyValue]) {
+    cached_counter("requests_total".into(), Some("Requests handled"))
+        .add(1, &metric_attributes(attributes));
+}
+
+///Count a failed request in `errors_total`, with the attributes given to `record_request`
+pub fn record_error_metric(attributes: &[KeyValue]) {
+    cached_counter("errors_total".into(), Some("Requests that failed"))
+        .add(1, &metric_attributes(attributes));
+}
+
+///Meter used by the convenience helpers
+pub(crate) fn meter() -> Meter {
+    global::meter_with_scope(default_scope())
+}
+
+///Counter `name` on observlib's meter, built on first use
+pub(crate) fn cached_counter(
+    name: Cow<'static, str>,
+    description: Option<&'static str>,
+) -> Counter<u64> {
+    let mut instruments = INSTRUMENTS.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(counter) = instruments.counters.get(name.as_ref()) {
+        return counter.clone();
+    }
+    let meter = meter();
+    let mut builder = meter.u64_counter(name.clone());
+    if let Some(description) = description {
+        builder = builder.with_description(description);
+    }
+    let counter = builder.build();
+    instruments.counters.insert(name, counter.clone());
+    counter
+}
+
+///Histogram `name` in seconds on observlib's meter, built on first use
+#[cfg(feature = "async")]
+pub(crate) fn cached_duration_histogram(
+    name: &'static str,
+    description: &'static str,
+) -> Histogram<f64> {
+    INSTRUMENTS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .histograms
+        .entry(name.into())
+        .or_insert_with(|| {
+            meter()
+                .f64_histogram(name)
+                .with_unit("s")
+                .with_description(description)
+                .build()
+        })
+        .clone()
+}
+
+///Register a gauge whose value is read from `callback` each time metrics are collected
+///
+/// This is the right pattern for sampled values such as a queue length, the callback runs
+/// on the exporter's thread at every export interval and should be cheap.
+///
+/// # Example
+/// ```no_run
+/// # use std::sync::Arc;
+/// # use std::sync::atomic::{AtomicUsize, Ordering};
+/// let queue_len = Arc::new(AtomicUsize::new(0));
+/// let len = queue_len.clone();
+/// let _gauge = observlib::observable_gauge("queue.length", move || len.load(Ordering::Relaxed) as f64);
+/// ```
+pub fn observable_gauge<F>(name: impl Into<Cow<'static, str>>, callback: F) -> ObservableGauge<f64>
+where
+    F: Fn() -> f64 + Send + Sync + 'static,
+{
+    meter()
+        .f64_observable_gauge(name)
+        .with_callback(move |observer| observer.observe(callback(), &metric_attributes(&[])))
         .build()
 }
+
+///Create a histogram on observlib's meter, with advisory bucket boundaries
+///
+/// `boundaries` are the upper bounds of the buckets, sorted and finite. They apply unless a
+/// view configures the instrument's aggregation, an empty slice keeps the SDK's defaults.
+///
+/// # Example
+/// ```no_run
+/// let latency = observlib::histogram("queue.wait", &[0.001, 0.01, 0.1, 1.0]);
+/// latency.record(0.004, &observlib::metric_attributes(&[]));
+/// ```
+pub fn histogram(name: impl Into<Cow<'static, str>>, boundaries: &[f64]) -> Histogram<f64> {
+    let mut name = name.into();
+    if let Some(prefix) = &*METRIC_PREFIX.read().unwrap_or_else(PoisonError::into_inner)
+        && !name.starts_with(prefix.as_str())
+    {
+        name = format!("{prefix}{name}").into();
+    }
+    let meter = meter();
+    let builder = meter.f64_histogram(name);
+    if boundaries.is_empty() {
+        builder.build()
+    } else {
+        builder.with_boundaries(boundaries.to_vec()).build()
+    }
+}
+
+///Gauge fed by an async source, see `async_observable_gauge`
+///
+/// Dropping it stops the refresh task, the gauge then keeps reporting the last value.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct AsyncObservableGauge {
+    _gauge: ObservableGauge<f64>,
+    refresh: tokio::task::AbortHandle,
+}
+
+#[cfg(feature = "async")]
+impl Drop for AsyncObservableGauge {
+    fn drop(&mut self) {
+        self.refresh.abort();
+    }
+}
+
+///Register a gauge whose value is produced by an async callback, such as a queue depth
+///fetched over the network
+///
+/// Metric collection only runs sync callbacks, so a tokio task awaits `callback` every
+/// `interval` and the gauge reports the last value it produced. Nothing is reported until
+/// the first call completed. Requires the `async` feature.
+///
+/// # Panics
+/// When called outside of a tokio runtime.
+///
+/// # Example
+/// ```no_run
+/// # use std::time::Duration;
+/// # async fn queue_depth() -> usize { 0 }
+/// # async fn run() {
+/// let _gauge = observlib::async_observable_gauge("queue.depth", Duration::from_secs(10), || async {
+///     queue_depth().await as f64
+/// });
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub fn async_observable_gauge<F, Fut>(
+    name: impl Into<Cow<'static, str>>,
+    interval: Duration,
+    callback: F,
+) -> AsyncObservableGauge
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = f64> + Send + 'static,
+{
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    let last = Arc::new(AtomicU64::new(f64::NAN.to_bits()));
+    let updated = last.clone();
+    let refresh = tokio::spawn(async move {
+        loop {
+            updated.store(callback().await.to_bits(), Ordering::Relaxed);
+            tokio::time::sleep(interval).await;
+        }
+    })
+    .abort_handle();
+    let gauge = meter()
+        .f64_observable_gauge(name)
+        .with_callback(move |observer| {
+            let value = f64::from_bits(last.load(Ordering::Relaxed));
+            if !value.is_nan() {
+                observer.observe(value, &metric_attributes(&[]));
+            }
+        })
+        .build();
+    AsyncObservableGauge {
+        _gauge: gauge,
+        refresh,
+    }
+}
+
+///Counter taking signed deltas, negative ones are logged and dropped instead of reaching
+///the SDK
+///
+/// For deltas computed from values that may go backwards, such as the difference between
+/// two readings of a counter that was reset.
+#[derive(Clone, Debug)]
+pub struct GuardedCounter {
+    name: Cow<'static, str>,
+    counter: Counter<u64>,
+}
+
+impl GuardedCounter {
+    ///Add `delta` if it is not negative, returns whether it was recorded
+    pub fn add(&self, delta: i64, attributes: &[KeyValue]) -> bool {
+        match u64::try_from(delta) {
+            Ok(delta) => {
+                self.counter.add(delta, &metric_attributes(attributes));
+                true
+            }
+            Err(_) => {
+                tracing::warn!(
+                    target: "observlib",
+                    counter = %self.name,
+                    delta,
+                    "ignoring negative delta on a monotonic counter"
+                );
+                false
+            }
+        }
+    }
+}
+
+///Create a `GuardedCounter` on observlib's meter
+pub fn guarded_counter(name: impl Into<Cow<'static, str>>) -> GuardedCounter {
+    let name = name.into();
+    GuardedCounter {
+        counter: meter().u64_counter(name.clone()).build(),
+        name,
+    }
+}
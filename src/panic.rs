@@ -0,0 +1,52 @@
+use opentelemetry::trace::{Status, TraceContextExt};
+use opentelemetry::{Context, KeyValue};
+use std::panic::PanicHookInfo;
+
+///Report panics as error events, tagged with the trace and span active on the panicking thread
+///
+/// The previous hook still runs afterwards, so the usual message is printed as well.
+pub(crate) fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        report_panic(info);
+        previous(info);
+    }));
+}
+
+fn report_panic(info: &PanicHookInfo<'_>) {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| info.payload().downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>");
+    let location = info
+        .location()
+        .map(|location| location.to_string())
+        .unwrap_or_default();
+
+    let cx = Context::current();
+    let span = cx.span();
+    let span_context = span.span_context();
+    if !span_context.is_valid() {
+        tracing::error!(target: "observlib::panic", location, "panic: {message}");
+        return;
+    }
+
+    tracing::error!(
+        target: "observlib::panic",
+        location,
+        trace_id = %span_context.trace_id(),
+        span_id = %span_context.span_id(),
+        "panic: {message}"
+    );
+    span.add_event(
+        "exception",
+        vec![
+            KeyValue::new("exception.type", "panic"),
+            KeyValue::new("exception.message", message.to_string()),
+            KeyValue::new("code.location", location),
+        ],
+    );
+    span.set_status(Status::error(format!("panic: {message}")));
+}
@@ -0,0 +1,158 @@
+use crate::metrics::{meter, metric_attributes};
+use opentelemetry::metrics::ObservableGauge;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+///Process CPU and memory usage, sampled by a background thread and exported as gauges
+///
+/// The `process.cpu.utilization` gauge is the share of the available CPUs used since the
+/// previous sample, `process.memory.usage` the resident set size in bytes. Samples come
+/// from `/proc/self`, so nothing is reported on other platforms. The thread stops when
+/// this is dropped along with the OtelManager.
+pub(crate) struct ProcessMetrics {
+    samples: Arc<Samples>,
+    thread: std::thread::Thread,
+    _gauges: [ObservableGauge<f64>; 2],
+}
+
+///Latest readings, stored as f64 bits, NaN until a first sample succeeded
+#[derive(Debug)]
+struct Samples {
+    cpu_utilization: AtomicU64,
+    memory_usage: AtomicU64,
+    stopped: AtomicBool,
+}
+
+impl Samples {
+    fn load(value: &AtomicU64) -> Option<f64> {
+        let value = f64::from_bits(value.load(Ordering::Relaxed));
+        (!value.is_nan()).then_some(value)
+    }
+
+    fn store(value: &AtomicU64, sample: f64) {
+        value.store(sample.to_bits(), Ordering::Relaxed);
+    }
+}
+
+impl ProcessMetrics {
+    pub(crate) fn start(interval: Duration) -> Self {
+        let samples = Arc::new(Samples {
+            cpu_utilization: AtomicU64::new(f64::NAN.to_bits()),
+            memory_usage: AtomicU64::new(f64::NAN.to_bits()),
+            stopped: AtomicBool::new(false),
+        });
+
+        let sampled = samples.clone();
+        let thread = std::thread::Builder::new()
+            .name("observlib-process-metrics".to_string())
+            .spawn(move || {
+                let cpus = std::thread::available_parallelism().map_or(1, usize::from) as f64;
+                let mut previous = cpu_time().map(|cpu| (cpu, Instant::now()));
+                while !sampled.stopped.load(Ordering::Acquire) {
+                    if let Some(memory) = memory_usage() {
+                        Samples::store(&sampled.memory_usage, memory as f64);
+                    }
+                    std::thread::park_timeout(interval);
+                    let Some(cpu) = cpu_time() else {
+                        continue;
+                    };
+                    let now = Instant::now();
+                    if let Some((previous_cpu, previous_time)) = previous {
+                        let elapsed = now.duration_since(previous_time).as_secs_f64();
+                        if elapsed > 0.0 {
+                            let used = cpu.saturating_sub(previous_cpu).as_secs_f64();
+                            Samples::store(&sampled.cpu_utilization, used / elapsed / cpus);
+                        }
+                    }
+                    previous = Some((cpu, now));
+                }
+            })
+            .expect("Failed to spawn the process metrics sampler");
+
+        let meter = meter();
+        let cpu = samples.clone();
+        let memory = samples.clone();
+        let gauges = [
+            meter
+                .f64_observable_gauge("process.cpu.utilization")
+                .with_unit("1")
+                .with_description("Share of the available CPUs used by the process")
+                .with_callback(move |observer| {
+                    if let Some(value) = Samples::load(&cpu.cpu_utilization) {
+                        observer.observe(value, &metric_attributes(&[]));
+                    }
+                })
+                .build(),
+            meter
+                .f64_observable_gauge("process.memory.usage")
+                .with_unit("By")
+                .with_description("Resident memory of the process")
+                .with_callback(move |observer| {
+                    if let Some(value) = Samples::load(&memory.memory_usage) {
+                        observer.observe(value, &metric_attributes(&[]));
+                    }
+                })
+                .build(),
+        ];
+
+        ProcessMetrics {
+            samples,
+            thread: thread.thread().clone(),
+            _gauges: gauges,
+        }
+    }
+}
+
+impl Drop for ProcessMetrics {
+    fn drop(&mut self) {
+        self.samples.stopped.store(true, Ordering::Release);
+        self.thread.unpark();
+    }
+}
+
+#[cfg(target_os = "linux")]
+unsafe extern "C" {
+    fn sysconf(name: std::ffi::c_int) -> std::ffi::c_long;
+}
+
+///User and system CPU time consumed by the process so far
+#[cfg(target_os = "linux")]
+fn cpu_time() -> Option<Duration> {
+    const SC_CLK_TCK: std::ffi::c_int = 2;
+
+    let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+    // The command name in parentheses may contain spaces, fields are counted after it.
+    let mut fields = stat.rsplit_once(')')?.1.split_whitespace();
+    let utime: u64 = fields.nth(11)?.parse().ok()?;
+    let stime: u64 = fields.next()?.parse().ok()?;
+    // SAFETY: sysconf only reads a configuration value.
+    let ticks_per_second = u64::try_from(unsafe { sysconf(SC_CLK_TCK) }).ok()?;
+    (ticks_per_second > 0)
+        .then(|| Duration::from_secs_f64((utime + stime) as f64 / ticks_per_second as f64))
+}
+
+///Resident set size of the process in bytes
+#[cfg(target_os = "linux")]
+fn memory_usage() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kilobytes = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kilobytes * 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn cpu_time() -> Option<Duration> {
+    None
+}
+
+#[cfg(not(target_os = "linux"))]
+fn memory_usage() -> Option<u64> {
+    None
+}
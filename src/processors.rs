@@ -0,0 +1,811 @@
+use crate::exporters::fit_span;
+use crate::metrics::metric_attributes;
+use crate::scope::default_scope;
+use crate::semconv::SemconvValidator;
+use opentelemetry::metrics::{Histogram, MeterProvider};
+use opentelemetry::trace::{SpanId, Status, TraceId};
+use opentelemetry::{Array, Context, InstrumentationScope, KeyValue, StringValue, Value};
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::error::OTelSdkResult;
+use opentelemetry_sdk::logs::{LogProcessor, SdkLogRecord};
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+use opentelemetry_sdk::trace::{Span, SpanData, SpanProcessor};
+use std::borrow::Cow;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+#[cfg(feature = "active-spans")]
+use std::time::SystemTime;
+use std::time::{Duration, Instant};
+
+///Match a span name against a pattern where `*` stands for any sequence of characters
+pub(crate) fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = name.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+fn span_duration(span: &SpanData) -> Duration {
+    span.end_time
+        .duration_since(span.start_time)
+        .unwrap_or_default()
+}
+
+///Records the duration of completed spans whose name matches one of the patterns
+#[derive(Debug)]
+pub(crate) struct SpanMetricsProcessor {
+    patterns: Vec<String>,
+    duration: Histogram<f64>,
+}
+
+impl SpanMetricsProcessor {
+    pub(crate) fn new(patterns: Vec<String>, meter_provider: &SdkMeterProvider) -> Self {
+        let duration = meter_provider
+            .meter_with_scope(default_scope())
+            .f64_histogram("span.duration")
+            .with_unit("s")
+            .with_description("Duration of spans matching the configured name patterns")
+            .build();
+        SpanMetricsProcessor { patterns, duration }
+    }
+}
+
+impl SpanProcessor for SpanMetricsProcessor {
+    fn on_start(&self, _span: &mut Span, _cx: &Context) {}
+
+    fn on_end(&self, span: SpanData) {
+        if !self
+            .patterns
+            .iter()
+            .any(|pattern| matches_pattern(pattern, &span.name))
+        {
+            return;
+        }
+
+        let elapsed = span_duration(&span);
+        let status = match span.status {
+            Status::Unset => "unset",
+            Status::Ok => "ok",
+            Status::Error { .. } => "error",
+        };
+        self.duration.record(
+            elapsed.as_secs_f64(),
+            &metric_attributes(&[
+                KeyValue::new("span.name", span.name),
+                KeyValue::new("span.kind", format!("{:?}", span.span_kind)),
+                KeyValue::new("status", status),
+            ]),
+        );
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        Ok(())
+    }
+
+    fn shutdown_with_timeout(&self, _timeout: Duration) -> OTelSdkResult {
+        Ok(())
+    }
+}
+
+///Forwards to the wrapped processor only the spans whose name matches none of the patterns
+#[derive(Debug)]
+pub(crate) struct SpanFilterProcessor<P> {
+    excluded: Vec<String>,
+    inner: P,
+}
+
+impl<P: SpanProcessor> SpanFilterProcessor<P> {
+    pub(crate) fn new(excluded: Vec<String>, inner: P) -> Self {
+        SpanFilterProcessor { excluded, inner }
+    }
+}
+
+impl<P: SpanProcessor> SpanProcessor for SpanFilterProcessor<P> {
+    fn on_start(&self, span: &mut Span, cx: &Context) {
+        self.inner.on_start(span, cx);
+    }
+
+    fn on_end(&self, span: SpanData) {
+        if self
+            .excluded
+            .iter()
+            .any(|pattern| matches_pattern(pattern, &span.name))
+        {
+            return;
+        }
+        self.inner.on_end(span);
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+}
+
+///Truncates string attribute values of spans and their events before handing them on
+///
+/// The SDK only bounds how many attributes a span keeps, backends also reject values past
+/// a length. Values are cut on a character boundary, strings inside arrays included.
+#[derive(Debug)]
+pub(crate) struct AttributeLengthProcessor<P> {
+    max_length: Option<usize>,
+    inner: P,
+}
+
+impl<P: SpanProcessor> AttributeLengthProcessor<P> {
+    pub(crate) fn new(max_length: Option<usize>, inner: P) -> Self {
+        AttributeLengthProcessor { max_length, inner }
+    }
+}
+
+impl<P: SpanProcessor> SpanProcessor for AttributeLengthProcessor<P> {
+    fn on_start(&self, span: &mut Span, cx: &Context) {
+        self.inner.on_start(span, cx);
+    }
+
+    fn on_end(&self, mut span: SpanData) {
+        if let Some(max_length) = self.max_length {
+            let events = span.events.events.iter_mut();
+            for attribute in span
+                .attributes
+                .iter_mut()
+                .chain(events.flat_map(|event| event.attributes.iter_mut()))
+            {
+                truncate_value(&mut attribute.value, max_length);
+            }
+        }
+        self.inner.on_end(span);
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+}
+
+fn truncate_value(value: &mut Value, max_length: usize) {
+    match value {
+        Value::String(string) => truncate_string(string, max_length),
+        Value::Array(Array::String(strings)) => {
+            for string in strings {
+                truncate_string(string, max_length);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn truncate_string(string: &mut StringValue, max_length: usize) {
+    if let Some((end, _)) = string.as_str().char_indices().nth(max_length) {
+        *string = StringValue::from(string.as_str()[..end].to_string());
+    }
+}
+
+///Trims spans estimated larger than the export batch byte limit before handing them on
+///
+/// This runs when the span ends rather than in the exporter, whose thread suppresses
+/// telemetry, so the warning naming the span reaches the exported logs too.
+#[derive(Debug)]
+pub(crate) struct OversizedSpanProcessor<P> {
+    max_bytes: Option<usize>,
+    inner: P,
+}
+
+impl<P: SpanProcessor> OversizedSpanProcessor<P> {
+    pub(crate) fn new(max_bytes: Option<usize>, inner: P) -> Self {
+        OversizedSpanProcessor { max_bytes, inner }
+    }
+}
+
+impl<P: SpanProcessor> SpanProcessor for OversizedSpanProcessor<P> {
+    fn on_start(&self, span: &mut Span, cx: &Context) {
+        self.inner.on_start(span, cx);
+    }
+
+    fn on_end(&self, mut span: SpanData) {
+        if let Some(max_bytes) = self.max_bytes {
+            fit_span(&mut span, max_bytes);
+        }
+        self.inner.on_end(span);
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+}
+
+///Traces buffered at once by `TraceBuffer`, the oldest is evicted past that
+const MAX_BUFFERED_TRACES: usize = 4096;
+
+///Traces whose root ended that `TraceBuffer` remembers, to handle their late children
+const MAX_DECIDED_TRACES: usize = 4096;
+
+///Child spans buffered per trace until the local root span of the trace ends
+///
+/// Both the buffered traces and the decisions made when roots ended are bounded, oldest
+/// first, so traces whose root never ends here or children ending long after it cannot
+/// grow memory without limit.
+#[derive(Debug)]
+pub(crate) struct TraceBuffer<T> {
+    pending: HashMap<TraceId, Vec<T>>,
+    ///Insertion order of `pending`, may still hold ids of traces decided since
+    pending_order: VecDeque<TraceId>,
+    decided: HashMap<TraceId, bool>,
+    decided_order: VecDeque<TraceId>,
+}
+
+impl<T> Default for TraceBuffer<T> {
+    fn default() -> Self {
+        TraceBuffer {
+            pending: HashMap::new(),
+            pending_order: VecDeque::new(),
+            decided: HashMap::new(),
+            decided_order: VecDeque::new(),
+        }
+    }
+}
+
+impl<T> TraceBuffer<T> {
+    ///Buffer `child`, or hand it back with the decision when its root already ended
+    pub(crate) fn push(&mut self, trace_id: TraceId, child: T) -> Option<(bool, T)> {
+        if let Some(&keep) = self.decided.get(&trace_id) {
+            return Some((keep, child));
+        }
+        match self.pending.entry(trace_id) {
+            Entry::Occupied(children) => children.into_mut().push(child),
+            Entry::Vacant(children) => {
+                children.insert(vec![child]);
+                self.pending_order.push_back(trace_id);
+                while self.pending.len() > MAX_BUFFERED_TRACES
+                    && let Some(oldest) = self.pending_order.pop_front()
+                {
+                    self.pending.remove(&oldest);
+                }
+                if self.pending_order.len() > 2 * MAX_BUFFERED_TRACES {
+                    self.pending_order
+                        .retain(|trace_id| self.pending.contains_key(trace_id));
+                }
+            }
+        }
+        None
+    }
+
+    ///Take the children buffered for a trace whose root ended, later ones get `keep`
+    pub(crate) fn decide(&mut self, trace_id: TraceId, keep: bool) -> Vec<T> {
+        if self.decided.insert(trace_id, keep).is_none() {
+            self.decided_order.push_back(trace_id);
+            if self.decided_order.len() > MAX_DECIDED_TRACES
+                && let Some(oldest) = self.decided_order.pop_front()
+            {
+                self.decided.remove(&oldest);
+            }
+        }
+        self.pending.remove(&trace_id).unwrap_or_default()
+    }
+}
+
+///Exports only the traces whose local root span lasted at least the threshold
+///
+/// Spans are held back until the root span of their trace ends in this process, then the
+/// whole trace is exported or dropped depending on the root's duration. Children ending
+/// after their root follow the decision made for it. Traces whose root has not ended by
+/// shutdown, or that were evicted from the bounded buffer, are dropped.
+#[derive(Debug)]
+pub(crate) struct LatencySamplingProcessor<P> {
+    threshold: Option<Duration>,
+    pending: Mutex<TraceBuffer<SpanData>>,
+    inner: P,
+}
+
+impl<P: SpanProcessor> LatencySamplingProcessor<P> {
+    pub(crate) fn new(threshold: Option<Duration>, inner: P) -> Self {
+        LatencySamplingProcessor {
+            threshold,
+            pending: Mutex::new(TraceBuffer::default()),
+            inner,
+        }
+    }
+}
+
+impl<P: SpanProcessor> SpanProcessor for LatencySamplingProcessor<P> {
+    fn on_start(&self, span: &mut Span, cx: &Context) {
+        self.inner.on_start(span, cx);
+    }
+
+    fn on_end(&self, span: SpanData) {
+        let Some(threshold) = self.threshold else {
+            return self.inner.on_end(span);
+        };
+        let trace_id = span.span_context.trace_id();
+        let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+        if span.parent_span_id != SpanId::INVALID && !span.parent_span_is_remote {
+            if let Some((true, late_child)) = pending.push(trace_id, span) {
+                drop(pending);
+                self.inner.on_end(late_child);
+            }
+            return;
+        }
+        let keep = span_duration(&span) >= threshold;
+        let children = pending.decide(trace_id, keep);
+        drop(pending);
+        if !keep {
+            return;
+        }
+        for child in children {
+            self.inner.on_end(child);
+        }
+        self.inner.on_end(span);
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+}
+
+///Warns about span attribute keys that look like misspelled semantic conventions
+#[derive(Debug)]
+pub(crate) struct SemconvSpanProcessor(pub(crate) Arc<SemconvValidator>);
+
+impl SpanProcessor for SemconvSpanProcessor {
+    fn on_start(&self, _span: &mut Span, _cx: &Context) {}
+
+    fn on_end(&self, span: SpanData) {
+        for attribute in &span.attributes {
+            self.0
+                .check(attribute.key.as_str(), format_args!("span {}", span.name));
+        }
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        Ok(())
+    }
+
+    fn shutdown_with_timeout(&self, _timeout: Duration) -> OTelSdkResult {
+        Ok(())
+    }
+}
+
+///Name and duration of a span nested under a root span
+type StageTiming = (Cow<'static, str>, Duration);
+
+///Logs, when a local root span ends, how long the spans nested under it took
+///
+/// Durations are summed per span name and listed longest first. Children ending after
+/// their root are left out of the report. Traces whose root never ends in this process are
+/// buffered until evicted from the bounded buffer.
+#[derive(Debug, Default)]
+pub(crate) struct SpanTimingProcessor {
+    children: Mutex<TraceBuffer<StageTiming>>,
+}
+
+impl SpanTimingProcessor {
+    fn report(root: &SpanData, children: Vec<StageTiming>) {
+        let mut per_name: Vec<StageTiming> = Vec::new();
+        for (name, duration) in children {
+            match per_name.iter_mut().find(|(known, _)| *known == name) {
+                Some((_, total)) => *total += duration,
+                None => per_name.push((name, duration)),
+            }
+        }
+        per_name.sort_by_key(|(_, total)| std::cmp::Reverse(*total));
+
+        let mut breakdown = String::new();
+        for (name, duration) in &per_name {
+            if !breakdown.is_empty() {
+                breakdown.push_str(", ");
+            }
+            let _ = write!(breakdown, "{name}={:.3}ms", duration.as_secs_f64() * 1000.0);
+        }
+        tracing::info!(
+            target: "observlib::timing",
+            root = %root.name,
+            trace_id = %root.span_context.trace_id(),
+            total_ms = span_duration(root).as_secs_f64() * 1000.0,
+            breakdown = %breakdown,
+            "span timing report"
+        );
+    }
+}
+
+impl SpanProcessor for SpanTimingProcessor {
+    fn on_start(&self, _span: &mut Span, _cx: &Context) {}
+
+    fn on_end(&self, span: SpanData) {
+        let trace_id = span.span_context.trace_id();
+        let mut children = self.children.lock().unwrap_or_else(PoisonError::into_inner);
+        if span.parent_span_id == SpanId::INVALID || span.parent_span_is_remote {
+            let trace_children = children.decide(trace_id, false);
+            drop(children);
+            Self::report(&span, trace_children);
+        } else {
+            children.push(trace_id, (span.name.clone(), span_duration(&span)));
+        }
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        Ok(())
+    }
+
+    fn shutdown_with_timeout(&self, _timeout: Duration) -> OTelSdkResult {
+        Ok(())
+    }
+}
+
+///Deals ended spans in turn to several processors, each batch processor exporting on
+///its own thread
+#[derive(Debug)]
+pub(crate) struct ShardedSpanProcessor<P> {
+    shards: Vec<P>,
+    next: AtomicUsize,
+}
+
+impl<P: SpanProcessor> ShardedSpanProcessor<P> {
+    pub(crate) fn new(shards: Vec<P>) -> Self {
+        ShardedSpanProcessor {
+            shards,
+            next: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<P: SpanProcessor> SpanProcessor for ShardedSpanProcessor<P> {
+    fn on_start(&self, span: &mut Span, cx: &Context) {
+        for shard in &self.shards {
+            shard.on_start(span, cx);
+        }
+    }
+
+    fn on_end(&self, span: SpanData) {
+        let next = self.next.fetch_add(1, Ordering::Relaxed);
+        self.shards[next % self.shards.len()].on_end(span);
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.shards
+            .iter()
+            .map(SpanProcessor::force_flush)
+            .fold(Ok(()), Result::and)
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.shards
+            .iter()
+            .map(|shard| shard.shutdown_with_timeout(timeout))
+            .fold(Ok(()), Result::and)
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        for shard in &mut self.shards {
+            shard.set_resource(resource);
+        }
+    }
+}
+
+///Log counterpart of `ShardedSpanProcessor`
+#[derive(Debug)]
+pub(crate) struct ShardedLogProcessor<P> {
+    shards: Vec<P>,
+    next: AtomicUsize,
+}
+
+impl<P: LogProcessor> ShardedLogProcessor<P> {
+    pub(crate) fn new(shards: Vec<P>) -> Self {
+        ShardedLogProcessor {
+            shards,
+            next: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<P: LogProcessor> LogProcessor for ShardedLogProcessor<P> {
+    fn emit(&self, data: &mut SdkLogRecord, instrumentation: &InstrumentationScope) {
+        let next = self.next.fetch_add(1, Ordering::Relaxed);
+        self.shards[next % self.shards.len()].emit(data, instrumentation);
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.shards
+            .iter()
+            .map(LogProcessor::force_flush)
+            .fold(Ok(()), Result::and)
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.shards
+            .iter()
+            .map(|shard| shard.shutdown_with_timeout(timeout))
+            .fold(Ok(()), Result::and)
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        for shard in &mut self.shards {
+            shard.set_resource(resource);
+        }
+    }
+}
+
+///Number of spans started but not ended yet, so shutdown can wait for them
+#[derive(Debug)]
+pub(crate) struct InFlightSpans {
+    open: AtomicUsize,
+    grace_period: Duration,
+}
+
+impl InFlightSpans {
+    pub(crate) fn new(grace_period: Duration) -> Self {
+        InFlightSpans {
+            open: AtomicUsize::new(0),
+            grace_period,
+        }
+    }
+
+    ///Block until every open span ended or the grace period elapsed
+    pub(crate) fn drain(&self) {
+        let deadline = Instant::now() + self.grace_period;
+        while self.open.load(Ordering::Acquire) > 0 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct InFlightSpanProcessor(pub(crate) Arc<InFlightSpans>);
+
+impl SpanProcessor for InFlightSpanProcessor {
+    fn on_start(&self, _span: &mut Span, _cx: &Context) {
+        self.0.open.fetch_add(1, Ordering::AcqRel);
+    }
+
+    fn on_end(&self, _span: SpanData) {
+        self.0.open.fetch_sub(1, Ordering::AcqRel);
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        Ok(())
+    }
+
+    fn shutdown_with_timeout(&self, _timeout: Duration) -> OTelSdkResult {
+        Ok(())
+    }
+}
+
+///Warns about spans still open after `threshold`, which usually means a guard was leaked
+///
+/// A background thread checks the open spans a few times per threshold and warns once
+/// per span on the `observlib::watchdog` target. Only recording spans are tracked.
+#[derive(Debug)]
+pub(crate) struct SpanWatchdogProcessor {
+    state: Arc<WatchdogState>,
+    thread: std::thread::Thread,
+}
+
+#[derive(Debug)]
+struct WatchdogState {
+    threshold: Duration,
+    open: Mutex<HashMap<SpanId, OpenSpan>>,
+    stopped: AtomicBool,
+}
+
+#[derive(Debug)]
+struct OpenSpan {
+    name: Cow<'static, str>,
+    trace_id: TraceId,
+    started: Instant,
+    warned: bool,
+}
+
+impl SpanWatchdogProcessor {
+    pub(crate) fn new(threshold: Duration) -> Self {
+        let state = Arc::new(WatchdogState {
+            threshold,
+            open: Mutex::new(HashMap::new()),
+            stopped: AtomicBool::new(false),
+        });
+        let watched = state.clone();
+        let interval = (threshold / 4).clamp(Duration::from_millis(10), Duration::from_secs(1));
+        let thread = std::thread::Builder::new()
+            .name("observlib-span-watchdog".to_string())
+            .spawn(move || {
+                while !watched.stopped.load(Ordering::Acquire) {
+                    std::thread::park_timeout(interval);
+                    watched.check();
+                }
+            })
+            .expect("Failed to spawn the span watchdog");
+        SpanWatchdogProcessor {
+            state,
+            thread: thread.thread().clone(),
+        }
+    }
+
+    fn stop(&self) {
+        self.state.stopped.store(true, Ordering::Release);
+        self.thread.unpark();
+    }
+}
+
+impl WatchdogState {
+    fn check(&self) {
+        let mut open = self.open.lock().unwrap_or_else(PoisonError::into_inner);
+        for (span_id, span) in open.iter_mut() {
+            let elapsed = span.started.elapsed();
+            if span.warned || elapsed < self.threshold {
+                continue;
+            }
+            span.warned = true;
+            tracing::warn!(
+                target: "observlib::watchdog",
+                span = %span.name,
+                trace_id = %span.trace_id,
+                span_id = %span_id,
+                open_ms = elapsed.as_millis() as u64,
+                "span still open past the watchdog threshold, it may never be ended"
+            );
+        }
+    }
+}
+
+impl SpanProcessor for SpanWatchdogProcessor {
+    fn on_start(&self, span: &mut Span, _cx: &Context) {
+        let Some(data) = span.exported_data() else {
+            return;
+        };
+        let open = OpenSpan {
+            name: data.name,
+            trace_id: data.span_context.trace_id(),
+            started: Instant::now(),
+            warned: false,
+        };
+        self.state
+            .open
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(data.span_context.span_id(), open);
+    }
+
+    fn on_end(&self, span: SpanData) {
+        self.state
+            .open
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&span.span_context.span_id());
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        Ok(())
+    }
+
+    fn shutdown_with_timeout(&self, _timeout: Duration) -> OTelSdkResult {
+        self.stop();
+        Ok(())
+    }
+}
+
+impl Drop for SpanWatchdogProcessor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+///Span state shared between the tracer provider's processors and the OtelManager
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SpanTrackers {
+    pub(crate) in_flight: Option<Arc<InFlightSpans>>,
+    #[cfg(feature = "active-spans")]
+    pub(crate) active: Arc<ActiveSpans>,
+}
+
+///A span that started and has not ended yet
+#[cfg(feature = "active-spans")]
+#[derive(Clone, Debug)]
+pub struct ActiveSpan {
+    pub name: Cow<'static, str>,
+    pub trace_id: TraceId,
+    pub span_id: SpanId,
+    pub start_time: SystemTime,
+}
+
+#[cfg(feature = "active-spans")]
+#[derive(Debug, Default)]
+pub(crate) struct ActiveSpans(Mutex<HashMap<SpanId, ActiveSpan>>);
+
+#[cfg(feature = "active-spans")]
+impl ActiveSpans {
+    ///Open spans, oldest first
+    pub(crate) fn list(&self) -> Vec<ActiveSpan> {
+        let mut spans: Vec<ActiveSpan> = self
+            .0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+            .cloned()
+            .collect();
+        spans.sort_by_key(|span| span.start_time);
+        spans
+    }
+}
+
+#[cfg(feature = "active-spans")]
+#[derive(Debug)]
+pub(crate) struct ActiveSpanProcessor(pub(crate) Arc<ActiveSpans>);
+
+#[cfg(feature = "active-spans")]
+impl SpanProcessor for ActiveSpanProcessor {
+    fn on_start(&self, span: &mut Span, _cx: &Context) {
+        let Some(data) = span.exported_data() else {
+            return;
+        };
+        let span_id = data.span_context.span_id();
+        let active = ActiveSpan {
+            name: data.name,
+            trace_id: data.span_context.trace_id(),
+            span_id,
+            start_time: data.start_time,
+        };
+        self.0
+            .0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(span_id, active);
+    }
+
+    fn on_end(&self, span: SpanData) {
+        self.0
+            .0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&span.span_context.span_id());
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        Ok(())
+    }
+
+    fn shutdown_with_timeout(&self, _timeout: Duration) -> OTelSdkResult {
+        Ok(())
+    }
+}
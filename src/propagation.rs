@@ -0,0 +1,182 @@
+use opentelemetry::baggage::{Baggage, BaggageExt};
+use opentelemetry::propagation::text_map_propagator::FieldIter;
+use opentelemetry::propagation::{
+    Extractor, Injector, TextMapCompositePropagator, TextMapPropagator,
+};
+use opentelemetry::{Context, global};
+use opentelemetry_sdk::propagation::{BaggagePropagator, TraceContextPropagator};
+use std::sync::OnceLock;
+
+///Incoming header requesting full capture of the request's trace
+pub(crate) const DEBUG_TRACE_HEADER: &str = "x-debug-trace";
+///Baggage entry carrying the debug request to the sampler and downstream services
+pub(crate) const DEBUG_TRACE_BAGGAGE: &str = "debug.trace";
+
+///Propagate W3C trace context and baggage across process boundaries, plus the debug trace
+///header when `debug_trace` is set
+pub(crate) fn install_propagator(debug_trace: bool) {
+    let mut propagators: Vec<Box<dyn TextMapPropagator + Send + Sync>> = vec![
+        Box::new(TraceContextPropagator::new()),
+        Box::new(BaggagePropagator::new()),
+    ];
+    // After the baggage propagator, which replaces the baggage it extracts.
+    if debug_trace {
+        propagators.push(Box::new(DebugTracePropagator));
+    }
+    global::set_text_map_propagator(TextMapCompositePropagator::new(propagators));
+}
+
+///Whether `cx` carries a debug trace request, from the header or from baggage
+pub(crate) fn is_debug_trace(cx: &Context) -> bool {
+    cx.baggage()
+        .get(DEBUG_TRACE_BAGGAGE)
+        .is_some_and(|value| is_enabled(value.as_str()))
+}
+
+fn is_enabled(value: &str) -> bool {
+    matches!(value.trim(), "1" | "true")
+}
+
+///Turns an incoming `X-Debug-Trace: 1` header into the `debug.trace` baggage entry
+///
+/// Baggage then carries the request to downstream services, nothing is injected here.
+#[derive(Debug)]
+struct DebugTracePropagator;
+
+impl TextMapPropagator for DebugTracePropagator {
+    fn inject_context(&self, _cx: &Context, _injector: &mut dyn Injector) {}
+
+    fn extract_with_context(&self, cx: &Context, extractor: &dyn Extractor) -> Context {
+        if !extractor.get(DEBUG_TRACE_HEADER).is_some_and(is_enabled) {
+            return cx.clone();
+        }
+        let mut baggage: Baggage = cx
+            .baggage()
+            .iter()
+            .map(|(key, entry)| (key.clone(), entry.clone()))
+            .collect();
+        baggage.insert(DEBUG_TRACE_BAGGAGE, "1");
+        cx.with_baggage(baggage)
+    }
+
+    fn fields(&self) -> FieldIter<'_> {
+        static FIELDS: OnceLock<[String; 1]> = OnceLock::new();
+        FieldIter::new(FIELDS.get_or_init(|| [DEBUG_TRACE_HEADER.to_string()]))
+    }
+}
+
+///Write the current context into an outgoing carrier such as request headers
+pub fn inject_context(carrier: &mut dyn Injector) {
+    global::get_text_map_propagator(|propagator| propagator.inject(carrier));
+}
+
+///Read the remote context of an incoming carrier, to be used as parent of the handling span
+pub fn extract_context(carrier: &dyn Extractor) -> Context {
+    global::get_text_map_propagator(|propagator| propagator.extract(carrier))
+}
+
+///`std::thread::spawn` running `f` within the caller's current context
+///
+/// Spans started on the new thread are then children of the caller's span, as they would
+/// be on the spawning thread.
+///
+/// # Example
+/// ```no_run
+/// use opentelemetry::{Context, trace::TraceContextExt};
+///
+/// let span = observlib::start_root_span("import");
+/// let _guard = Context::current_with_span(span).attach();
+/// let worker = observlib::spawn_with_context(|| {
+///     // spans started here belong to the import trace
+/// });
+/// worker.join().unwrap();
+/// ```
+pub fn spawn_with_context<F, T>(f: F) -> std::thread::JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let cx = Context::current();
+    std::thread::spawn(move || {
+        let _guard = cx.attach();
+        f()
+    })
+}
+
+#[cfg(feature = "tonic")]
+pub use grpc::{
+    MetadataExtractor, MetadataInjector, extract_grpc_context, grpc_context, inject_grpc_context,
+};
+
+#[cfg(feature = "tonic")]
+mod grpc {
+    use super::{extract_context, inject_context};
+    use opentelemetry::Context;
+    use opentelemetry::propagation::{Extractor, Injector};
+    use tonic::metadata::{KeyRef, MetadataKey, MetadataMap, MetadataValue};
+    use tonic::{Request, Status};
+
+    ///Carrier writing propagation fields into gRPC metadata
+    pub struct MetadataInjector<'a>(pub &'a mut MetadataMap);
+
+    impl Injector for MetadataInjector<'_> {
+        fn set(&mut self, key: &str, value: String) {
+            if let (Ok(key), Ok(value)) = (
+                MetadataKey::from_bytes(key.as_bytes()),
+                MetadataValue::try_from(value),
+            ) {
+                self.0.insert(key, value);
+            }
+        }
+    }
+
+    ///Carrier reading propagation fields from gRPC metadata
+    pub struct MetadataExtractor<'a>(pub &'a MetadataMap);
+
+    impl Extractor for MetadataExtractor<'_> {
+        fn get(&self, key: &str) -> Option<&str> {
+            self.0.get(key).and_then(|value| value.to_str().ok())
+        }
+
+        fn keys(&self) -> Vec<&str> {
+            self.0
+                .keys()
+                .filter_map(|key| match key {
+                    KeyRef::Ascii(key) => Some(key.as_str()),
+                    KeyRef::Binary(_) => None,
+                })
+                .collect()
+        }
+    }
+
+    ///Client interceptor sending the current context along with each call
+    ///
+    /// # Example
+    /// ```ignore
+    /// let client = GreeterClient::with_interceptor(channel, observlib::inject_grpc_context);
+    /// ```
+    pub fn inject_grpc_context(mut request: Request<()>) -> Result<Request<()>, Status> {
+        inject_context(&mut MetadataInjector(request.metadata_mut()));
+        Ok(request)
+    }
+
+    ///Server interceptor storing the caller's context in the request extensions
+    ///
+    /// Handlers retrieve it with `grpc_context` to parent their spans:
+    /// ```ignore
+    /// let server = GreeterServer::with_interceptor(service, observlib::extract_grpc_context);
+    /// ```
+    pub fn extract_grpc_context(mut request: Request<()>) -> Result<Request<()>, Status> {
+        let cx = extract_context(&MetadataExtractor(request.metadata()));
+        request.extensions_mut().insert(cx);
+        Ok(request)
+    }
+
+    ///Context stored by `extract_grpc_context`, or the remote context read from the metadata
+    pub fn grpc_context<T>(request: &Request<T>) -> Context {
+        match request.extensions().get::<Context>() {
+            Some(cx) => cx.clone(),
+            None => extract_context(&MetadataExtractor(request.metadata())),
+        }
+    }
+}
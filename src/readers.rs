@@ -0,0 +1,108 @@
+use crate::exporters::SwappableExporter;
+use opentelemetry_otlp::MetricExporter;
+use opentelemetry_sdk::error::OTelSdkResult;
+use opentelemetry_sdk::metrics::data::ResourceMetrics;
+use opentelemetry_sdk::metrics::exporter::PushMetricExporter;
+use opentelemetry_sdk::metrics::reader::MetricReader;
+use opentelemetry_sdk::metrics::{InstrumentKind, ManualReader, Pipeline, Temporality};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+///Exports metrics at wall-clock multiples of the interval, e.g. on every minute boundary
+///
+/// The SDK's periodic reader counts its interval from startup, so replicas started at
+/// different times export at different offsets within each window.
+#[derive(Debug)]
+pub(crate) struct AlignedReader {
+    export: Arc<AlignedExport>,
+    worker: Mutex<Option<(Sender<()>, JoinHandle<()>)>>,
+}
+
+#[derive(Debug)]
+struct AlignedExport {
+    reader: ManualReader,
+    exporter: SwappableExporter<MetricExporter>,
+}
+
+impl AlignedExport {
+    fn collect_and_export(&self) -> OTelSdkResult {
+        let mut metrics = ResourceMetrics::default();
+        self.reader.collect(&mut metrics)?;
+        futures_executor::block_on(self.exporter.export(&metrics))
+    }
+}
+
+impl AlignedReader {
+    pub(crate) fn new(exporter: SwappableExporter<MetricExporter>, interval: Duration) -> Self {
+        let export = Arc::new(AlignedExport {
+            reader: ManualReader::builder()
+                .with_temporality(exporter.temporality())
+                .build(),
+            exporter,
+        });
+        let (stop, stopped) = mpsc::channel();
+        let worker_export = export.clone();
+        let handle = std::thread::Builder::new()
+            .name("observlib-aligned-metrics".to_string())
+            .spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) =
+                    stopped.recv_timeout(until_next_boundary(interval))
+                {
+                    if let Err(e) = worker_export.collect_and_export() {
+                        tracing::warn!(target: "observlib", "aligned metrics export failed: {e}");
+                    }
+                }
+            })
+            .expect("Failed to spawn the aligned metrics thread");
+        AlignedReader {
+            export,
+            worker: Mutex::new(Some((stop, handle))),
+        }
+    }
+}
+
+///Time left until the next multiple of `interval` since the unix epoch
+fn until_next_boundary(interval: Duration) -> Duration {
+    let interval = interval.as_nanos().max(1);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    Duration::from_nanos((interval - now % interval) as u64)
+}
+
+impl MetricReader for AlignedReader {
+    fn register_pipeline(&self, pipeline: Weak<Pipeline>) {
+        self.export.reader.register_pipeline(pipeline);
+    }
+
+    fn collect(&self, rm: &mut ResourceMetrics) -> OTelSdkResult {
+        self.export.reader.collect(rm)
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.export.collect_and_export()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        let worker = self
+            .worker
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        if let Some((stop, handle)) = worker {
+            let _ = stop.send(());
+            let _ = handle.join();
+        }
+        let exported = self.export.collect_and_export();
+        self.export.reader.shutdown_with_timeout(timeout)?;
+        self.export.exporter.shutdown_with_timeout(timeout)?;
+        exported
+    }
+
+    fn temporality(&self, kind: InstrumentKind) -> Temporality {
+        self.export.reader.temporality(kind)
+    }
+}
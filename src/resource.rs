@@ -0,0 +1,101 @@
+use opentelemetry::KeyValue;
+#[cfg(feature = "attributes-file")]
+use opentelemetry::{Array, Value};
+#[cfg(feature = "attributes-file")]
+use serde_json::Value as Json;
+#[cfg(feature = "attributes-file")]
+use std::path::Path;
+use std::sync::OnceLock;
+
+pub(crate) const SERVICE_INSTANCE_ID: &str = "service.instance.id";
+pub(crate) const SERVICE_NAMESPACE: &str = "service.namespace";
+
+///Downward API environment variables and the resource attribute each one maps to
+const K8S_ENV_ATTRIBUTES: [(&str, &str); 4] = [
+    ("POD_NAME", "k8s.pod.name"),
+    ("POD_NAMESPACE", "k8s.namespace.name"),
+    ("POD_UID", "k8s.pod.uid"),
+    ("NODE_NAME", "k8s.node.name"),
+];
+
+///Kubernetes attributes of the variables exposed to the container, unset ones are skipped
+pub(crate) fn k8s_attributes() -> Vec<KeyValue> {
+    K8S_ENV_ATTRIBUTES
+        .iter()
+        .filter_map(|(var, key)| {
+            std::env::var(var)
+                .ok()
+                .filter(|value| !value.is_empty())
+                .map(|value| KeyValue::new(*key, value))
+        })
+        .collect()
+}
+
+///Random id generated once per process, so that replicas of a service can be told apart
+pub(crate) fn service_instance_id() -> &'static str {
+    static INSTANCE_ID: OnceLock<String> = OnceLock::new();
+    INSTANCE_ID.get_or_init(|| uuid::Uuid::new_v4().to_string())
+}
+
+///Attributes of a JSON or YAML file holding a flat map of keys to values
+///
+/// The format follows the extension: `.json`, `.yaml` or `.yml`. Strings, booleans,
+/// integers, floats and arrays of one of those keep their type, other values are rejected.
+#[cfg(feature = "attributes-file")]
+pub(crate) fn file_attributes(path: &Path) -> Result<Vec<KeyValue>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let map: serde_json::Map<String, Json> = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => serde_json::from_str(&text).map_err(|e| e.to_string()),
+        Some("yaml" | "yml") => serde_yaml::from_str(&text).map_err(|e| e.to_string()),
+        _ => Err("expected a .json, .yaml or .yml file".to_string()),
+    }
+    .map_err(|e| format!("{}: {e}", path.display()))?;
+
+    map.into_iter()
+        .map(|(key, value)| match attribute_value(value) {
+            Some(value) => Ok(KeyValue::new(key, value)),
+            None => Err(format!(
+                "{}: {key} is not a string, boolean, number or array of one of those",
+                path.display()
+            )),
+        })
+        .collect()
+}
+
+#[cfg(feature = "attributes-file")]
+fn attribute_value(value: Json) -> Option<Value> {
+    Some(match value {
+        Json::String(text) => Value::from(text),
+        Json::Bool(flag) => Value::Bool(flag),
+        Json::Number(number) => match number.as_i64() {
+            Some(integer) => Value::I64(integer),
+            None => Value::F64(number.as_f64()?),
+        },
+        Json::Array(items) => Value::Array(array_value(items)?),
+        Json::Null | Json::Object(_) => return None,
+    })
+}
+
+///Arrays are homogeneous, integers mixed with floats become floats
+#[cfg(feature = "attributes-file")]
+fn array_value(items: Vec<Json>) -> Option<Array> {
+    Some(if items.iter().all(Json::is_string) {
+        Array::String(
+            items
+                .into_iter()
+                .filter_map(|item| match item {
+                    Json::String(text) => Some(text.into()),
+                    _ => None,
+                })
+                .collect(),
+        )
+    } else if items.iter().all(Json::is_boolean) {
+        Array::Bool(items.iter().filter_map(Json::as_bool).collect())
+    } else if items.iter().all(Json::is_i64) {
+        Array::I64(items.iter().filter_map(Json::as_i64).collect())
+    } else if items.iter().all(Json::is_number) {
+        Array::F64(items.iter().filter_map(Json::as_f64).collect())
+    } else {
+        return None;
+    })
+}
@@ -0,0 +1,123 @@
+use crate::propagation::{DEBUG_TRACE_BAGGAGE, is_debug_trace};
+use opentelemetry::trace::{
+    Link, SamplingDecision, SamplingResult, SpanKind, TraceContextExt, TraceId,
+};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry_sdk::trace::{Sampler, ShouldSample};
+
+///Sampler the tracer provider was built with
+#[derive(Clone, Debug, PartialEq)]
+pub struct SamplerInfo {
+    ///Name as spelled in OTEL_TRACES_SAMPLER (example: parentbased_traceidratio)
+    pub name: &'static str,
+    ///Whether sampled or dropped parents decide for their children
+    pub parent_based: bool,
+    ///Probability of sampling a root span
+    pub ratio: f64,
+}
+
+impl SamplerInfo {
+    ///Sampler selected by OTEL_TRACES_SAMPLER and OTEL_TRACES_SAMPLER_ARG
+    ///
+    /// Mirrors the SDK's handling: unknown or unsupported samplers fall back to
+    /// parentbased_always_on and a missing or invalid ratio to 1.0.
+    pub(crate) fn from_env() -> Self {
+        let ratio = std::env::var("OTEL_TRACES_SAMPLER_ARG")
+            .ok()
+            .and_then(|arg| arg.parse().ok())
+            .unwrap_or(1.0);
+        let sampler = std::env::var("OTEL_TRACES_SAMPLER").unwrap_or_default();
+        let (name, parent_based, ratio) = match sampler.as_str() {
+            "always_on" => ("always_on", false, 1.0),
+            "always_off" => ("always_off", false, 0.0),
+            "traceidratio" => ("traceidratio", false, ratio),
+            "parentbased_always_off" => ("parentbased_always_off", true, 0.0),
+            "parentbased_traceidratio" => ("parentbased_traceidratio", true, ratio),
+            _ => ("parentbased_always_on", true, 1.0),
+        };
+        SamplerInfo {
+            name,
+            parent_based,
+            ratio,
+        }
+    }
+
+    ///Sampler set on the builder, a ratio based one when `ratio` is given
+    pub(crate) fn configured(ratio: Option<f64>, parent_based: bool) -> Self {
+        let (name, ratio) = match (ratio, parent_based) {
+            (None, false) => ("always_on", 1.0),
+            (None, true) => ("parentbased_always_on", 1.0),
+            (Some(ratio), false) => ("traceidratio", ratio),
+            (Some(ratio), true) => ("parentbased_traceidratio", ratio),
+        };
+        SamplerInfo {
+            name,
+            parent_based,
+            ratio,
+        }
+    }
+
+    pub(crate) fn sampler(&self, debug_trace: bool) -> ConfiguredSampler {
+        let (root, ratio) = match self.name {
+            "always_on" | "parentbased_always_on" => (Sampler::AlwaysOn, None),
+            "always_off" | "parentbased_always_off" => (Sampler::AlwaysOff, None),
+            _ => (Sampler::TraceIdRatioBased(self.ratio), Some(self.ratio)),
+        };
+        let inner = if self.parent_based {
+            Sampler::ParentBased(Box::new(root))
+        } else {
+            root
+        };
+        ConfiguredSampler {
+            inner,
+            ratio,
+            debug_trace,
+        }
+    }
+}
+
+///Sampler from the environment, with observlib's additions
+///
+/// Root spans sampled by a ratio based sampler get `sampler.type` and `sampler.param` so
+/// backends can weigh sampled traces back to the real counts. With `debug_trace`, spans
+/// whose parent context asks for a debug trace are sampled whatever the inner decision.
+#[derive(Clone, Debug)]
+pub(crate) struct ConfiguredSampler {
+    inner: Sampler,
+    ratio: Option<f64>,
+    debug_trace: bool,
+}
+
+impl ShouldSample for ConfiguredSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        let mut result =
+            self.inner
+                .should_sample(parent_context, trace_id, name, span_kind, attributes, links);
+        if self.debug_trace && parent_context.is_some_and(is_debug_trace) {
+            result.decision = SamplingDecision::RecordAndSample;
+            result
+                .attributes
+                .push(KeyValue::new(DEBUG_TRACE_BAGGAGE, true));
+            return result;
+        }
+        let is_root = parent_context.is_none_or(|cx| !cx.span().span_context().is_valid());
+        if let Some(ratio) = self.ratio
+            && is_root
+            && result.decision == SamplingDecision::RecordAndSample
+        {
+            result.attributes.extend([
+                KeyValue::new("sampler.type", "traceidratio"),
+                KeyValue::new("sampler.param", ratio),
+            ]);
+        }
+        result
+    }
+}
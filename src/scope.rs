@@ -0,0 +1,72 @@
+use crate::SCOPE_NAME;
+use opentelemetry::global::{self, BoxedTracer};
+use opentelemetry::metrics::Meter;
+use opentelemetry::{InstrumentationScope, KeyValue};
+use std::borrow::Cow;
+use std::sync::{PoisonError, RwLock};
+
+///Attributes of the scope observlib's own helpers report under
+static DEFAULT_SCOPE_ATTRIBUTES: RwLock<Vec<KeyValue>> = RwLock::new(Vec::new());
+
+pub(crate) fn set_default_scope_attributes(attributes: Vec<KeyValue>) {
+    *DEFAULT_SCOPE_ATTRIBUTES
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = attributes;
+}
+
+///Scope of the tracer and meter used by observlib's helpers and processors
+pub(crate) fn default_scope() -> InstrumentationScope {
+    let attributes = DEFAULT_SCOPE_ATTRIBUTES
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    InstrumentationScope::builder(SCOPE_NAME)
+        .with_attributes(attributes)
+        .build()
+}
+
+///Tracer and meter of one logical service hosted in a shared process
+///
+/// The resource is process wide, so modules of a monolith are told apart by the attributes
+/// of their instrumentation scope instead, which backends expose next to resource attributes.
+///
+/// # Example
+/// ```no_run
+/// use observlib::{KeyValue, ScopedTelemetry};
+/// use opentelemetry::trace::Tracer;
+///
+/// let billing = ScopedTelemetry::new("billing", [KeyValue::new("module.name", "billing")]);
+/// billing.tracer().in_span("charge", |_cx| {});
+/// billing.meter().u64_counter("invoices").build().add(1, &[]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ScopedTelemetry {
+    scope: InstrumentationScope,
+}
+
+impl ScopedTelemetry {
+    pub fn new<T>(name: impl Into<Cow<'static, str>>, attributes: T) -> Self
+    where
+        T: IntoIterator<Item = KeyValue>,
+    {
+        ScopedTelemetry {
+            scope: InstrumentationScope::builder(name)
+                .with_attributes(attributes)
+                .build(),
+        }
+    }
+
+    pub fn scope(&self) -> &InstrumentationScope {
+        &self.scope
+    }
+
+    ///Tracer of the global provider reporting this scope
+    pub fn tracer(&self) -> BoxedTracer {
+        global::tracer_with_scope(self.scope.clone())
+    }
+
+    ///Meter of the global provider reporting this scope
+    pub fn meter(&self) -> Meter {
+        global::meter_with_scope(self.scope.clone())
+    }
+}
@@ -0,0 +1,65 @@
+use crate::metrics::{cached_counter, metric_attributes};
+use crate::traces::tracer;
+use crate::{ObservlibError, OtelManager, TelemetryBuilder};
+use opentelemetry::KeyValue;
+use opentelemetry::global::BoxedSpan;
+use opentelemetry::trace::Tracer;
+use std::borrow::Cow;
+
+///Telemetry operations application code depends on, so tests can inject a double
+///
+/// `OtelManager` implements it for real, `observlib::testing::MockTelemetry` records the
+/// calls instead. Code taking `&impl Telemetry` or `&dyn Telemetry` works with both.
+///
+/// # Example
+/// ```no_run
+/// use observlib::{KeyValue, Telemetry, TelemetryBuilder};
+/// use opentelemetry::trace::Span;
+///
+/// fn handle(telemetry: &dyn Telemetry) {
+///     let mut span = telemetry.span("handle".into(), &[]);
+///     telemetry.counter("handled".into(), 1, &[KeyValue::new("route", "/users")]);
+///     span.end();
+/// }
+///
+/// let otel = observlib::OtelManager::init(TelemetryBuilder::new("service")).unwrap();
+/// handle(&otel);
+/// otel.shutdown().unwrap();
+/// ```
+pub trait Telemetry: Send + Sync {
+    ///Set up telemetry from `config`
+    fn init(config: TelemetryBuilder) -> Result<Self, ObservlibError>
+    where
+        Self: Sized;
+
+    ///Start a child of the current span
+    fn span(&self, name: Cow<'static, str>, attributes: &[KeyValue]) -> BoxedSpan;
+
+    ///Add `value` to a monotonic counter
+    fn counter(&self, name: Cow<'static, str>, value: u64, attributes: &[KeyValue]);
+
+    ///Export what is buffered and stop
+    fn shutdown(&self) -> Result<(), ObservlibError>;
+}
+
+impl Telemetry for OtelManager {
+    fn init(config: TelemetryBuilder) -> Result<Self, ObservlibError> {
+        config.build()
+    }
+
+    fn span(&self, name: Cow<'static, str>, attributes: &[KeyValue]) -> BoxedSpan {
+        let tracer = tracer();
+        tracer
+            .span_builder(name)
+            .with_attributes(attributes.to_vec())
+            .start(&tracer)
+    }
+
+    fn counter(&self, name: Cow<'static, str>, value: u64, attributes: &[KeyValue]) {
+        cached_counter(name, None).add(value, &metric_attributes(attributes));
+    }
+
+    fn shutdown(&self) -> Result<(), ObservlibError> {
+        OtelManager::shutdown(self)
+    }
+}
@@ -0,0 +1,357 @@
+/*!
+In-process OTLP/HTTP collector for tests, enabled by the `testing` feature
+
+It accepts every export request, answers 200 and keeps the request for assertions, so
+tests can exercise the real export path without running a collector.
+
+# Example
+```no_run
+use observlib::TelemetryBuilder;
+use observlib::testing::MockCollector;
+use opentelemetry::trace::Span;
+
+let collector = MockCollector::start();
+let otel = TelemetryBuilder::new("service")
+    .with_endpoint(collector.endpoint())
+    .with_logs_enabled(false)
+    .without_subscriber()
+    .build()
+    .unwrap();
+observlib::start_root_span("checked").end();
+otel.shutdown().unwrap();
+
+let spans: Vec<_> = collector
+    .traces()
+    .into_iter()
+    .flat_map(|request| request.resource_spans)
+    .flat_map(|resource| resource.scope_spans)
+    .flat_map(|scope| scope.spans)
+    .collect();
+assert_eq!(spans[0].name, "checked");
+```
+*/
+use crate::{ObservlibError, Telemetry, TelemetryBuilder};
+use opentelemetry::KeyValue;
+use opentelemetry::global::{BoxedSpan, BoxedTracer};
+use opentelemetry::trace::Tracer;
+use opentelemetry::trace::noop::NoopTracer;
+pub use opentelemetry_proto::tonic::collector::logs::v1::ExportLogsServiceRequest;
+pub use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
+pub use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+use prost::Message;
+use std::borrow::Cow;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+///An export request as received by the mock collector
+#[derive(Clone, Debug)]
+pub struct CapturedRequest {
+    ///Request path, `/v1/traces`, `/v1/metrics` or `/v1/logs` for the OTLP exporters
+    pub path: String,
+    ///Header names are lowercased
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl CapturedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+///OTLP/HTTP server listening on an ephemeral localhost port until dropped with the process
+#[derive(Clone, Debug)]
+pub struct MockCollector {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<CapturedRequest>>>,
+}
+
+impl MockCollector {
+    ///Bind an ephemeral port and serve from a background thread
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind the mock collector");
+        let addr = listener
+            .local_addr()
+            .expect("Mock collector has no address");
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let captured = requests.clone();
+        std::thread::Builder::new()
+            .name("observlib-mock-collector".to_string())
+            .spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let captured = captured.clone();
+                    std::thread::spawn(move || serve(stream, &captured));
+                }
+            })
+            .expect("Failed to spawn the mock collector");
+        MockCollector { addr, requests }
+    }
+
+    ///Address to pass to `TelemetryBuilder::with_endpoint`
+    pub fn endpoint(&self) -> String {
+        self.addr.to_string()
+    }
+
+    ///Every request received so far, in arrival order
+    pub fn requests(&self) -> Vec<CapturedRequest> {
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    ///Remove and return every request received so far, for assertions made phase by phase
+    pub fn drain(&self) -> Vec<CapturedRequest> {
+        std::mem::take(&mut *self.requests.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    ///Wait until a request reached `path`, returns whether one did before the timeout
+    pub fn wait_for(&self, path: &str, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if self.requests().iter().any(|request| request.path == path) {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    ///Trace export requests, compressed bodies are skipped
+    pub fn traces(&self) -> Vec<ExportTraceServiceRequest> {
+        self.decode("/v1/traces")
+    }
+
+    ///Metric export requests, compressed bodies are skipped
+    pub fn metrics(&self) -> Vec<ExportMetricsServiceRequest> {
+        self.decode("/v1/metrics")
+    }
+
+    ///Log export requests, compressed bodies are skipped
+    pub fn logs(&self) -> Vec<ExportLogsServiceRequest> {
+        self.decode("/v1/logs")
+    }
+
+    ///Remove the trace export requests and return them, other requests are kept
+    pub fn drain_traces(&self) -> Vec<ExportTraceServiceRequest> {
+        decode_all(&self.take("/v1/traces"))
+    }
+
+    ///Remove the metric export requests and return them, other requests are kept
+    pub fn drain_metrics(&self) -> Vec<ExportMetricsServiceRequest> {
+        decode_all(&self.take("/v1/metrics"))
+    }
+
+    ///Remove the log export requests and return them, other requests are kept
+    pub fn drain_logs(&self) -> Vec<ExportLogsServiceRequest> {
+        decode_all(&self.take("/v1/logs"))
+    }
+
+    fn take(&self, path: &str) -> Vec<CapturedRequest> {
+        let mut requests = self.requests.lock().unwrap_or_else(PoisonError::into_inner);
+        let (taken, kept) = std::mem::take(&mut *requests)
+            .into_iter()
+            .partition(|request| request.path == path);
+        *requests = kept;
+        taken
+    }
+
+    fn decode<M: Message + Default>(&self, path: &str) -> Vec<M> {
+        let requests: Vec<_> = self
+            .requests()
+            .into_iter()
+            .filter(|request| request.path == path)
+            .collect();
+        decode_all(&requests)
+    }
+}
+
+///Decode the uncompressed bodies of `requests`
+fn decode_all<M: Message + Default>(requests: &[CapturedRequest]) -> Vec<M> {
+    requests
+        .iter()
+        .filter(|request| request.header("content-encoding").is_none())
+        .filter_map(|request| M::decode(request.body.as_slice()).ok())
+        .collect()
+}
+
+///Serve the requests of one keep-alive connection
+fn serve(stream: TcpStream, requests: &Mutex<Vec<CapturedRequest>>) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    let mut reader = BufReader::new(stream);
+    while let Some(request) = read_request(&mut reader) {
+        requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(request);
+        let response =
+            "HTTP/1.1 200 OK\r\ncontent-type: application/x-protobuf\r\ncontent-length: 0\r\n\r\n";
+        if writer.write_all(response.as_bytes()).is_err() {
+            return;
+        }
+    }
+}
+
+fn read_request(reader: &mut impl BufRead) -> Option<CapturedRequest> {
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).ok()? == 0 {
+        return None;
+    }
+    let path = request_line.split_whitespace().nth(1)?.to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).ok()?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line.split_once(':')?;
+        headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+    }
+
+    let length = headers
+        .iter()
+        .find(|(name, _)| name == "content-length")
+        .and_then(|(_, value)| value.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+
+    Some(CapturedRequest {
+        path,
+        headers,
+        body,
+    })
+}
+
+///A call made through the `Telemetry` trait, as recorded by `MockTelemetry`
+#[derive(Clone, Debug, PartialEq)]
+pub enum TelemetryCall {
+    Init {
+        service_name: &'static str,
+    },
+    Span {
+        name: Cow<'static, str>,
+        attributes: Vec<KeyValue>,
+    },
+    Counter {
+        name: Cow<'static, str>,
+        value: u64,
+        attributes: Vec<KeyValue>,
+    },
+    Shutdown,
+}
+
+///`Telemetry` double recording calls instead of exporting, for consumers' unit tests
+///
+/// Nothing global is installed, so any number of mocks can live in the same test binary.
+/// Spans it returns are no-ops. Clones share the recorded calls.
+///
+/// # Example
+/// ```
+/// use observlib::Telemetry;
+/// use observlib::testing::{MockTelemetry, TelemetryCall};
+///
+/// let telemetry = MockTelemetry::new();
+/// telemetry.counter("handled".into(), 1, &[]);
+/// assert_eq!(telemetry.counter_total("handled"), 1);
+/// assert!(matches!(telemetry.calls()[0], TelemetryCall::Counter { .. }));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MockTelemetry {
+    calls: Arc<Mutex<Vec<TelemetryCall>>>,
+}
+
+impl MockTelemetry {
+    pub fn new() -> Self {
+        MockTelemetry::default()
+    }
+
+    ///Every call so far, in order
+    pub fn calls(&self) -> Vec<TelemetryCall> {
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    ///Names of the spans started so far, in order
+    pub fn span_names(&self) -> Vec<Cow<'static, str>> {
+        self.calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                TelemetryCall::Span { name, .. } => Some(name),
+                _ => None,
+            })
+            .collect()
+    }
+
+    ///Sum of the values added to the counter `name`, whatever their attributes
+    pub fn counter_total(&self, name: &str) -> u64 {
+        self.calls()
+            .iter()
+            .filter_map(|call| match call {
+                TelemetryCall::Counter {
+                    name: counter,
+                    value,
+                    ..
+                } if counter == name => Some(value),
+                _ => None,
+            })
+            .sum()
+    }
+
+    ///Whether `shutdown` was called
+    pub fn is_shut_down(&self) -> bool {
+        self.calls().contains(&TelemetryCall::Shutdown)
+    }
+
+    fn record(&self, call: TelemetryCall) {
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(call);
+    }
+}
+
+impl Telemetry for MockTelemetry {
+    fn init(config: TelemetryBuilder) -> Result<Self, ObservlibError> {
+        let mock = MockTelemetry::new();
+        mock.record(TelemetryCall::Init {
+            service_name: config.service_name,
+        });
+        Ok(mock)
+    }
+
+    fn span(&self, name: Cow<'static, str>, attributes: &[KeyValue]) -> BoxedSpan {
+        self.record(TelemetryCall::Span {
+            name: name.clone(),
+            attributes: attributes.to_vec(),
+        });
+        BoxedTracer::new(Box::new(NoopTracer::new())).start(name)
+    }
+
+    fn counter(&self, name: Cow<'static, str>, value: u64, attributes: &[KeyValue]) {
+        self.record(TelemetryCall::Counter {
+            name,
+            value,
+            attributes: attributes.to_vec(),
+        });
+    }
+
+    fn shutdown(&self) -> Result<(), ObservlibError> {
+        self.record(TelemetryCall::Shutdown);
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use crate::ObservlibError;
+use opentelemetry_http::HttpError;
+use reqwest::{Certificate, Identity};
+use std::error::Error;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug)]
+enum Pem {
+    File(PathBuf),
+    Bytes(Vec<u8>),
+}
+
+impl Pem {
+    fn read(&self) -> Result<Vec<u8>, ObservlibError> {
+        match self {
+            Pem::File(path) => std::fs::read(path)
+                .map_err(|e| ObservlibError::Tls(format!("{}: {e}", path.display()))),
+            Pem::Bytes(bytes) => Ok(bytes.clone()),
+        }
+    }
+}
+
+///TLS settings of the OTLP exporters, PEM material can come from files or memory
+///
+/// In-memory PEM fits secrets injected through environment variables or a vault, which
+/// never need to be written to disk.
+///
+/// The collector is verified against the system CA store. Minimal container images often
+/// have none, the `webpki-roots` feature bundles Mozilla's root certificates in the binary
+/// and trusts them as well.
+///
+/// # Example
+/// ```no_run
+/// # use observlib::{TelemetryBuilder, TlsConfig};
+/// let ca = std::env::var("COLLECTOR_CA").unwrap();
+/// let otel = TelemetryBuilder::new("service")
+///     .with_endpoint("collector.internal:4318")
+///     .with_tls(TlsConfig::new().with_ca_pem(ca))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TlsConfig {
+    ca: Option<Pem>,
+    client_cert: Option<(Pem, Pem)>,
+}
+
+impl TlsConfig {
+    ///Verify the collector with the system roots, and the bundled ones with `webpki-roots`
+    pub fn new() -> Self {
+        TlsConfig::default()
+    }
+
+    ///Also trust the CA certificate found in this PEM file
+    pub fn with_ca_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ca = Some(Pem::File(path.into()));
+        self
+    }
+
+    ///Also trust this PEM encoded CA certificate
+    pub fn with_ca_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.ca = Some(Pem::Bytes(pem.into()));
+        self
+    }
+
+    ///Authenticate with the client certificate and private key found in these PEM files
+    pub fn with_client_cert_files(
+        mut self,
+        cert_path: impl Into<PathBuf>,
+        key_path: impl Into<PathBuf>,
+    ) -> Self {
+        self.client_cert = Some((Pem::File(cert_path.into()), Pem::File(key_path.into())));
+        self
+    }
+
+    ///Authenticate with this PEM encoded client certificate and private key
+    pub fn with_client_cert_pem(
+        mut self,
+        cert_pem: impl Into<Vec<u8>>,
+        key_pem: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.client_cert = Some((Pem::Bytes(cert_pem.into()), Pem::Bytes(key_pem.into())));
+        self
+    }
+
+    ///Configure the exporters' http client, failing on unreadable or malformed PEM
+    pub(crate) fn apply(
+        &self,
+        mut builder: reqwest::blocking::ClientBuilder,
+    ) -> Result<reqwest::blocking::ClientBuilder, ObservlibError> {
+        if let Some(pem) = &self.ca {
+            let ca = Certificate::from_pem(&pem.read()?)
+                .map_err(|e| ObservlibError::Tls(format!("CA certificate: {e}")))?;
+            builder = builder.add_root_certificate(ca);
+        }
+        if let Some((cert, key)) = &self.client_cert {
+            let mut pem = cert.read()?;
+            pem.push(b'\n');
+            pem.extend(key.read()?);
+            let identity = Identity::from_pem(&pem)
+                .map_err(|e| ObservlibError::Tls(format!("client certificate: {e}")))?;
+            builder = builder.identity(identity);
+        }
+        Ok(builder)
+    }
+}
+
+///`error` reworded when it comes from the collector's certificate failing verification
+///
+/// The TLS library's own message is kept, it ends up deep in the exporter's error otherwise.
+pub(crate) fn explain_verification_failure(error: HttpError) -> HttpError {
+    let mut source: Option<&(dyn Error + 'static)> = Some(error.as_ref());
+    while let Some(cause) = source {
+        if cause.to_string().contains("certificate") {
+            let hint = if cfg!(feature = "webpki-roots") {
+                "trust its CA with TlsConfig::with_ca_file"
+            } else {
+                "trust its CA with TlsConfig::with_ca_file or enable the webpki-roots feature"
+            };
+            return format!("collector certificate verification failed ({cause}), {hint}").into();
+        }
+        source = cause.source();
+    }
+    error
+}
@@ -1,18 +1,482 @@
-use opentelemetry_otlp::WithExportConfig;
+#[cfg(feature = "grpc")]
+use crate::builder::Transport;
+use crate::dynamic_attributes::DynamicAttributeSpanProcessor;
+use crate::exporters::{ExporterHandles, SwappableExporter};
+use crate::http_client::{ExportClient, export_headers, signal_url};
+#[cfg(feature = "grpc")]
+use crate::http_client::{collector_url, export_metadata};
+#[cfg(feature = "active-spans")]
+use crate::processors::ActiveSpanProcessor;
+use crate::processors::{
+    AttributeLengthProcessor, InFlightSpanProcessor, InFlightSpans, LatencySamplingProcessor,
+    OversizedSpanProcessor, SemconvSpanProcessor, ShardedSpanProcessor, SpanFilterProcessor,
+    SpanMetricsProcessor, SpanTimingProcessor, SpanTrackers, SpanWatchdogProcessor,
+};
+use crate::sampling::SamplerInfo;
+use crate::scope::default_scope;
+use crate::semconv::SemconvValidator;
+use crate::{ObservlibError, TelemetryBuilder};
+use opentelemetry::global::{self, BoxedSpan, BoxedTracer};
+use opentelemetry::trace::{SpanId, Status, TraceContextExt, TraceId, Tracer};
+use opentelemetry::{Context, KeyValue};
+#[cfg(feature = "grpc")]
+use opentelemetry_otlp::WithTonicConfig;
 use opentelemetry_otlp::{Protocol, SpanExporter};
+use opentelemetry_otlp::{WithExportConfig, WithHttpConfig};
 use opentelemetry_sdk::Resource;
-use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::metrics::SdkMeterProvider;
+use opentelemetry_sdk::trace::{
+    BatchConfigBuilder, BatchSpanProcessor, IdGenerator, SdkTracerProvider, TracerProviderBuilder,
+};
+use std::borrow::Cow;
+use std::error::Error;
+use std::sync::Arc;
 
-pub fn init_traces(resource: Resource, endpoint: &str) -> SdkTracerProvider {
-    let exporter = SpanExporter::builder()
+pub fn init_traces(
+    resource: Resource,
+    config: &TelemetryBuilder,
+    client: Option<&ExportClient>,
+    meter_provider: Option<&SdkMeterProvider>,
+    semconv: Option<Arc<SemconvValidator>>,
+    exporters: &mut ExporterHandles,
+) -> Result<(SdkTracerProvider, SpanTrackers, SamplerInfo), ObservlibError> {
+    let new_exporter = || {
+        let exporter = SwappableExporter::new(
+            span_exporter(config, client)?,
+            config.stdout_fallback,
+            config.traces.max_batch_bytes,
+        );
+        exporters.traces.push(exporter.clone());
+        Ok(exporter)
+    };
+
+    let sampler = match (config.sampling_ratio, config.parent_based_sampling) {
+        (None, None) => SamplerInfo::from_env(),
+        (ratio, parent_based) => SamplerInfo::configured(ratio, parent_based.unwrap_or(false)),
+    };
+    let mut builder = SdkTracerProvider::builder()
+        .with_sampler(sampler.sampler(config.debug_trace_header))
+        .with_span_processor(DynamicAttributeSpanProcessor);
+    if let Some(max_attributes) = config.span_limits.max_attributes_per_span {
+        builder = builder.with_max_attributes_per_span(max_attributes);
+    }
+    if let Some(id_generator) = &config.id_generator {
+        builder = builder.with_id_generator(SharedIdGenerator(id_generator.clone()));
+    }
+    if let Some(meter_provider) = meter_provider
+        && !config.span_metrics_patterns.is_empty()
+    {
+        builder = builder.with_span_processor(SpanMetricsProcessor::new(
+            config.span_metrics_patterns.clone(),
+            meter_provider,
+        ));
+    }
+
+    if let Some(semconv) = semconv {
+        builder = builder.with_span_processor(SemconvSpanProcessor(semconv));
+    }
+
+    if config.span_timing_reports {
+        builder = builder.with_span_processor(SpanTimingProcessor::default());
+    }
+
+    if let Some(threshold) = config.span_watchdog_threshold {
+        builder = builder.with_span_processor(SpanWatchdogProcessor::new(threshold));
+    }
+
+    let mut trackers = SpanTrackers::default();
+    if let Some(grace_period) = config.shutdown_grace_period {
+        let in_flight = Arc::new(InFlightSpans::new(grace_period));
+        builder = builder.with_span_processor(InFlightSpanProcessor(in_flight.clone()));
+        trackers.in_flight = Some(in_flight);
+    }
+    #[cfg(feature = "active-spans")]
+    {
+        builder = builder.with_span_processor(ActiveSpanProcessor(trackers.active.clone()));
+    }
+
+    builder = with_export(builder, new_exporter, config)?;
+    #[cfg(feature = "jaeger")]
+    if let Some(jaeger_endpoint) = &config.jaeger_endpoint {
+        let new_exporter = || {
+            let mut exporter = SpanExporter::builder()
+                .with_http()
+                .with_protocol(Protocol::HttpBinary)
+                .with_endpoint(format!("http://{jaeger_endpoint}/v1/traces"));
+            if let Some(compression) = config.traces.compression {
+                exporter = exporter.with_compression(compression);
+            }
+            exporter
+                .build()
+                .map_err(|e| ObservlibError::TraceExporterBuild(format!("jaeger: {e}")))
+        };
+        builder = with_export(builder, new_exporter, config)?;
+    }
+
+    let provider = builder.with_resource(resource).build();
+    Ok((provider, trackers, sampler))
+}
+
+///OTLP exporter of the configured endpoint
+pub(crate) fn span_exporter(
+    config: &TelemetryBuilder,
+    client: Option<&ExportClient>,
+) -> Result<SpanExporter, ObservlibError> {
+    #[cfg(feature = "grpc")]
+    if config.transport == Transport::Grpc {
+        let mut exporter = SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(collector_url(config, &config.traces))
+            .with_metadata(export_metadata(config, &config.traces));
+        if let Some(timeout) = config.traces.timeout {
+            exporter = exporter.with_timeout(timeout);
+        }
+        return exporter
+            .build()
+            .map_err(|e| ObservlibError::TraceExporterBuild(e.to_string()));
+    }
+
+    let mut exporter = SpanExporter::builder()
         .with_http()
         .with_protocol(Protocol::HttpBinary) //can be changed to `Protocol::HttpJson` to export in JSON format
-        .with_endpoint(format!("http://{}/v1/traces", endpoint))
+        .with_endpoint(signal_url(config, &config.traces, "traces"))
+        .with_headers(export_headers(config, &config.traces));
+    if let Some(compression) = config.traces.compression {
+        exporter = exporter.with_compression(compression);
+    }
+    if let Some(timeout) = config.traces.timeout {
+        exporter = exporter.with_timeout(timeout);
+    }
+    if let Some(client) = client {
+        exporter = exporter.with_http_client(client.clone());
+    }
+    exporter
         .build()
-        .expect("Failed to create trace exporter");
+        .map_err(|e| ObservlibError::TraceExporterBuild(e.to_string()))
+}
 
-    SdkTracerProvider::builder()
-        .with_batch_exporter(exporter)
-        .with_resource(resource)
-        .build()
+///Export spans in batches through exporters from `new_exporter`, skipping the excluded ones
+///
+/// Each export thread owns a batch processor and its exporter, spans are dealt to them in turn.
+fn with_export<E: opentelemetry_sdk::trace::SpanExporter + 'static>(
+    builder: TracerProviderBuilder,
+    mut new_exporter: impl FnMut() -> Result<E, ObservlibError>,
+    config: &TelemetryBuilder,
+) -> Result<TracerProviderBuilder, ObservlibError> {
+    let batch_config = || {
+        let mut batch_config = BatchConfigBuilder::default();
+        if let Some(max_queue_size) = config.traces.max_queue_size {
+            batch_config = batch_config.with_max_queue_size(max_queue_size);
+        }
+        if let Some(max_export_batch_size) = config.traces.max_export_batch_size {
+            batch_config = batch_config.with_max_export_batch_size(max_export_batch_size);
+        }
+        if let Some(interval) = config.traces.export_interval {
+            batch_config = batch_config.with_scheduled_delay(interval);
+        }
+        batch_config.build()
+    };
+    let shards = (0..config.traces.export_threads.max(1))
+        .map(|_| {
+            Ok(BatchSpanProcessor::builder(new_exporter()?)
+                .with_batch_config(batch_config())
+                .build())
+        })
+        .collect::<Result<_, ObservlibError>>()?;
+    let batch = LatencySamplingProcessor::new(
+        config.latency_sampling_threshold,
+        AttributeLengthProcessor::new(
+            config.span_limits.max_attribute_value_length,
+            OversizedSpanProcessor::new(
+                config.traces.max_batch_bytes,
+                ShardedSpanProcessor::new(shards),
+            ),
+        ),
+    );
+    Ok(if config.excluded_span_patterns.is_empty() {
+        builder.with_span_processor(batch)
+    } else {
+        builder.with_span_processor(SpanFilterProcessor::new(
+            config.excluded_span_patterns.clone(),
+            batch,
+        ))
+    })
+}
+
+///Bounds on the attributes of exported spans, unset limits keep the SDK defaults
+///
+/// Attributes past the count are dropped, the latest ones first, while values past the
+/// length are truncated so the attribute is kept.
+///
+/// # Example
+/// ```no_run
+/// # use observlib::{SpanLimits, TelemetryBuilder};
+/// let otel = TelemetryBuilder::new("service")
+///     .with_span_limits(
+///         SpanLimits::new()
+///             .with_max_attributes_per_span(64)
+///             .with_max_attribute_value_length(4096),
+///     )
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SpanLimits {
+    pub(crate) max_attributes_per_span: Option<u32>,
+    pub(crate) max_attribute_value_length: Option<usize>,
+}
+
+impl SpanLimits {
+    pub fn new() -> Self {
+        SpanLimits::default()
+    }
+
+    ///Number of attributes a span keeps, 128 by default
+    pub fn with_max_attributes_per_span(mut self, max_attributes: u32) -> Self {
+        self.max_attributes_per_span = Some(max_attributes);
+        self
+    }
+
+    ///Number of characters string attribute values are truncated to, unlimited by default
+    pub fn with_max_attribute_value_length(mut self, max_length: usize) -> Self {
+        self.max_attribute_value_length = Some(max_length);
+        self
+    }
+}
+
+///Id generator configured on the builder, shared since init only borrows the configuration
+#[derive(Debug)]
+struct SharedIdGenerator(Arc<dyn IdGenerator>);
+
+impl IdGenerator for SharedIdGenerator {
+    fn new_trace_id(&self) -> TraceId {
+        self.0.new_trace_id()
+    }
+
+    fn new_span_id(&self) -> SpanId {
+        self.0.new_span_id()
+    }
+}
+
+///Tracer used by the convenience helpers
+pub(crate) fn tracer() -> BoxedTracer {
+    global::tracer_with_scope(default_scope())
+}
+
+///Start a span rooting a new trace, whatever context is currently active
+///
+/// For background jobs kicked off while a request is being handled, which would otherwise
+/// end up parented under that unrelated request.
+///
+/// # Example
+/// ```no_run
+/// use opentelemetry::Context;
+/// use opentelemetry::trace::TraceContextExt;
+///
+/// let span = observlib::start_root_span("nightly cleanup");
+/// let _guard = Context::current_with_span(span).attach();
+/// // spans started here belong to the new trace
+/// ```
+pub fn start_root_span(name: impl Into<Cow<'static, str>>) -> BoxedSpan {
+    let tracer = tracer();
+    tracer
+        .span_builder(name)
+        .start_with_context(&tracer, &Context::new())
+}
+
+///Name of a span started by observlib's helpers, fixed or computed from runtime data
+///
+/// Implemented for strings and for closures returning one, which the helpers only call
+/// when the span is created, so a `format!` costs nothing on skipped paths. Keeping the
+/// number of distinct names bounded is up to the caller.
+pub trait SpanName {
+    fn into_span_name(self) -> Cow<'static, str>;
+}
+
+impl SpanName for &'static str {
+    fn into_span_name(self) -> Cow<'static, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl SpanName for String {
+    fn into_span_name(self) -> Cow<'static, str> {
+        Cow::Owned(self)
+    }
+}
+
+impl SpanName for Cow<'static, str> {
+    fn into_span_name(self) -> Cow<'static, str> {
+        self
+    }
+}
+
+impl<F, S> SpanName for F
+where
+    F: FnOnce() -> S,
+    S: Into<Cow<'static, str>>,
+{
+    fn into_span_name(self) -> Cow<'static, str> {
+        self().into()
+    }
+}
+
+///Start a child of the current span only when `predicate` holds, for hot paths
+///
+/// When it does not, nothing is allocated and no sampling decision is made, so a flag read
+/// from an atomic keeps the disabled path close to free. `attributes` are only consumed
+/// and a closure `name` only called when the span is created.
+///
+/// # Example
+/// ```no_run
+/// use observlib::KeyValue;
+/// use opentelemetry::trace::Span;
+/// use std::sync::atomic::{AtomicBool, Ordering};
+///
+/// static TRACE_PARSER: AtomicBool = AtomicBool::new(false);
+///
+/// # let kind = "ident";
+/// let span = observlib::span_if(
+///     TRACE_PARSER.load(Ordering::Relaxed),
+///     || format!("parse {kind}"),
+///     [KeyValue::new("token.kind", kind)],
+/// );
+/// // ...
+/// if let Some(mut span) = span {
+///     span.end();
+/// }
+/// ```
+pub fn span_if(
+    predicate: bool,
+    name: impl SpanName,
+    attributes: impl IntoIterator<Item = KeyValue>,
+) -> Option<BoxedSpan> {
+    if !predicate {
+        return None;
+    }
+    let tracer = tracer();
+    Some(
+        tracer
+            .span_builder(name.into_span_name())
+            .with_attributes(attributes)
+            .start(&tracer),
+    )
+}
+
+///Run `future` inside a new child span of the current one and record its duration
+///
+/// The span's context is attached on every poll, so it stays current across `.await`
+/// points and on whichever thread resumes the future. The future is also instrumented with
+/// a `tracing` span named `instrument_future`, whose `operation` field holds `name`, so
+/// `tracing` spans and events inside it are nested under it. On completion the duration goes to
+/// the `operation.duration` histogram with an `operation.name` attribute and `attributes`,
+/// which the span gets as well. Dropping the future early ends the span without recording.
+/// `name` can be computed, see `SpanName`.
+///
+/// # Example
+/// ```no_run
+/// # async fn fetch() {}
+/// # async fn run() {
+/// use observlib::KeyValue;
+///
+/// observlib::instrument_future("fetch users", [KeyValue::new("db.system", "postgresql")], fetch())
+///     .await;
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn instrument_future<F: std::future::Future>(
+    name: impl SpanName,
+    attributes: impl IntoIterator<Item = KeyValue>,
+    future: F,
+) -> F::Output {
+    use opentelemetry::trace::FutureExt;
+    use tracing::Instrument;
+
+    let name = name.into_span_name();
+    let attributes: Vec<KeyValue> = attributes.into_iter().collect();
+    let tracer = tracer();
+    let span = tracer
+        .span_builder(name.clone())
+        .with_attributes(attributes.clone())
+        .start(&tracer);
+    let cx = Context::current_with_span(span);
+    let tracing_span = tracing::info_span!("instrument_future", operation = %name);
+    let start = std::time::Instant::now();
+    let output = future
+        .instrument(tracing_span)
+        .with_context(cx.clone())
+        .await;
+    let mut metric_attributes = vec![KeyValue::new("operation.name", name)];
+    metric_attributes.extend(attributes);
+    crate::metrics::cached_duration_histogram(
+        "operation.duration",
+        "Duration of futures run through instrument_future",
+    )
+    .record(
+        start.elapsed().as_secs_f64(),
+        &crate::metric_attributes(&metric_attributes),
+    );
+    cx.span().end();
+    output
+}
+
+///Record `error` as an exception event on the current span and mark the span as failed
+pub fn record_error(error: &dyn Error) {
+    let cx = Context::current();
+    let span = cx.span();
+    span.record_error(error);
+    span.set_status(Status::error(error.to_string()));
+}
+
+///Mark the current span as successful, e.g. after a handled fallback
+///
+/// Ok is final, the status can no longer be changed afterwards. Without an active span
+/// this does nothing.
+pub fn set_span_ok() {
+    Context::current().span().set_status(Status::Ok);
+}
+
+///Mark the current span as failed with `message`, without recording an exception event
+///
+/// Without an active span this does nothing.
+pub fn set_span_error(message: impl Into<Cow<'static, str>>) {
+    Context::current().span().set_status(Status::error(message));
+}
+
+///Record the error of a result on the current span, see `TraceResultExt::observe_err`
+pub fn trace_result<T, E: Error>(result: Result<T, E>) -> Result<T, E> {
+    result.observe_err()
+}
+
+///Record errors on the current span while propagating them unchanged
+///
+/// # Example
+/// ```no_run
+/// use observlib::TraceResultExt;
+///
+/// fn read_config() -> std::io::Result<String> {
+///     let raw = std::fs::read_to_string("config.toml").observe_err()?;
+///     Ok(raw)
+/// }
+/// ```
+pub trait TraceResultExt {
+    ///Record the error, if any, and hand the result back for `?`
+    fn observe_err(self) -> Self;
+
+    ///Record the error, if any, keeping ownership of the result with the caller
+    fn observe_err_ref(&self) -> &Self;
+}
+
+impl<T, E: Error> TraceResultExt for Result<T, E> {
+    fn observe_err(self) -> Self {
+        self.observe_err_ref();
+        self
+    }
+
+    fn observe_err_ref(&self) -> &Self {
+        if let Err(error) = self {
+            record_error(error);
+        }
+        self
+    }
 }
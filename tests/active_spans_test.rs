@@ -0,0 +1,17 @@
+#[cfg(feature = "active-spans")]
+#[test]
+pub fn active_spans_are_listed() {
+    use observlib::{TelemetryBuilder, global};
+    use opentelemetry::trace::{Span, Tracer};
+
+    let otel_manager = TelemetryBuilder::new("blah").build().unwrap();
+    let mut span = global::tracer("my tracer").start("pending work");
+
+    let active = otel_manager.active_spans();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].name, "pending work");
+
+    span.end();
+    assert!(otel_manager.active_spans().is_empty());
+    otel_manager.shutdown().unwrap();
+}
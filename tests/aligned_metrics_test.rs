@@ -0,0 +1,49 @@
+/// Periodic collections happen on wall-clock multiples of the interval.
+#[cfg(feature = "testing")]
+#[test]
+pub fn aligned_metrics_are_collected_on_interval_boundaries() {
+    use observlib::testing::MockCollector;
+    use observlib::{TelemetryBuilder, global};
+    use opentelemetry_proto::tonic::metrics::v1::metric::Data;
+    use std::time::Duration;
+
+    let interval = Duration::from_secs(1);
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("aligned")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_traces_enabled(false)
+        .without_subscriber()
+        .with_aligned_metrics_export(interval)
+        .build()
+        .unwrap();
+
+    let counter = global::meter("aligned meter")
+        .u64_counter("aligned_counter")
+        .build();
+    counter.add(1, &[]);
+    assert!(collector.wait_for("/v1/metrics", Duration::from_secs(5)));
+
+    let collected_at: Vec<u64> = collector
+        .metrics()
+        .into_iter()
+        .take(1)
+        .flat_map(|request| request.resource_metrics)
+        .flat_map(|resource| resource.scope_metrics)
+        .flat_map(|scope| scope.metrics)
+        .flat_map(|metric| match metric.data {
+            Some(Data::Sum(sum)) => sum.data_points,
+            _ => Vec::new(),
+        })
+        .map(|point| point.time_unix_nano)
+        .collect();
+    assert_eq!(collected_at.len(), 1);
+    let past_boundary = Duration::from_nanos(collected_at[0] % interval.as_nanos() as u64);
+    assert!(
+        past_boundary < Duration::from_millis(100),
+        "collected {past_boundary:?} after a boundary"
+    );
+
+    otel_manager.force_flush().unwrap();
+    otel_manager.shutdown().unwrap();
+}
@@ -0,0 +1,40 @@
+/// The value produced by the async callback is the one exported.
+#[cfg(all(feature = "async", feature = "testing"))]
+#[tokio::test(flavor = "multi_thread")]
+async fn async_gauge_reports_the_last_value() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry_proto::tonic::metrics::v1::metric::Data;
+    use opentelemetry_proto::tonic::metrics::v1::number_data_point::Value;
+    use std::time::Duration;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("async_gauge")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_traces_enabled(false)
+        .without_subscriber()
+        .build()
+        .unwrap();
+
+    let _gauge =
+        observlib::async_observable_gauge("queue.depth", Duration::from_millis(10), || async {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            7.0
+        });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    otel_manager.async_shutdown(None).await.unwrap();
+
+    let value = collector
+        .metrics()
+        .into_iter()
+        .flat_map(|request| request.resource_metrics)
+        .flat_map(|resource| resource.scope_metrics)
+        .flat_map(|scope| scope.metrics)
+        .filter(|metric| metric.name == "queue.depth")
+        .find_map(|metric| match metric.data? {
+            Data::Gauge(gauge) => gauge.data_points.first()?.value,
+            _ => None,
+        });
+    assert_eq!(value, Some(Value::AsDouble(7.0)));
+}
@@ -1,4 +1,6 @@
-use observlib::{global, KeyValue};
+#[cfg(feature = "async")]
+use observlib::{KeyValue, global};
+#[cfg(feature = "async")]
 use std::time::Duration;
 
 /// Comprehensive async shutdown test that covers:
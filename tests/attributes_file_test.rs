@@ -0,0 +1,67 @@
+#[cfg(feature = "attributes-file")]
+fn write(name: &str, contents: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("observlib-{}-{name}", std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[cfg(feature = "attributes-file")]
+#[test]
+pub fn file_attributes_keep_their_type() {
+    use observlib::TelemetryBuilder;
+    use opentelemetry::{Array, Key, Value};
+
+    let json = write(
+        "attributes.json",
+        r#"{"team": "payments", "replicas": 3, "ratio": 0.5, "canary": true, "zones": ["a", "b"]}"#,
+    );
+    let yaml = write("attributes.yaml", "region: eu-west-1\nports: [80, 443]\n");
+    let resource = TelemetryBuilder::new("blah")
+        .with_attributes_file(&json)
+        .with_attributes_file(&yaml)
+        .build_resource();
+
+    assert_eq!(
+        resource.get(&Key::new("team")),
+        Some(Value::from("payments"))
+    );
+    assert_eq!(resource.get(&Key::new("replicas")), Some(Value::I64(3)));
+    assert_eq!(resource.get(&Key::new("ratio")), Some(Value::F64(0.5)));
+    assert_eq!(resource.get(&Key::new("canary")), Some(Value::Bool(true)));
+    assert_eq!(
+        resource.get(&Key::new("zones")),
+        Some(Value::Array(Array::String(vec!["a".into(), "b".into()])))
+    );
+    assert_eq!(
+        resource.get(&Key::new("region")),
+        Some(Value::from("eu-west-1"))
+    );
+    assert_eq!(
+        resource.get(&Key::new("ports")),
+        Some(Value::Array(Array::I64(vec![80, 443])))
+    );
+}
+
+#[cfg(feature = "attributes-file")]
+#[test]
+pub fn malformed_files_are_rejected() {
+    use observlib::{ObservlibError, TelemetryBuilder};
+
+    let nested = write("nested.json", r#"{"owner": {"team": "payments"}}"#);
+    let malformed = write("malformed.yaml", "region: [eu-west-1\n");
+    let unknown = write("attributes.toml", "region = \"eu-west-1\"\n");
+    for path in [
+        nested,
+        malformed,
+        unknown,
+        std::path::PathBuf::from("/nonexistent/attributes.json"),
+    ] {
+        let result = TelemetryBuilder::new("blah")
+            .with_attributes_file(&path)
+            .build();
+        assert!(
+            matches!(result, Err(ObservlibError::AttributesFile(_))),
+            "{path:?}"
+        );
+    }
+}
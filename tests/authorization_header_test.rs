@@ -0,0 +1,31 @@
+/// A user Authorization header wins over endpoint credentials, whatever the case of its name.
+#[cfg(feature = "testing")]
+#[test]
+pub fn authorization_header_replaces_endpoint_credentials_whatever_its_case() {
+    use observlib::testing::MockCollector;
+    use observlib::{ExportOptions, TelemetryBuilder};
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("blah")
+        .with_endpoint(format!("http://user:secret@{}", collector.endpoint()))
+        .with_logs_enabled(false)
+        .with_traces_enabled(false)
+        .without_subscriber()
+        .with_metrics_export_options(
+            ExportOptions::new().with_header("Authorization", "Bearer token"),
+        )
+        .build()
+        .unwrap();
+    observlib::guarded_counter("authenticated").add(1, &[]);
+    otel_manager.shutdown().unwrap();
+
+    let requests = collector.requests();
+    assert!(!requests.is_empty());
+    let authorizations: Vec<_> = requests[0]
+        .headers
+        .iter()
+        .filter(|(name, _)| name == "authorization")
+        .map(|(_, value)| value.as_str())
+        .collect();
+    assert_eq!(authorizations, ["Bearer token"]);
+}
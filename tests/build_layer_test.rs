@@ -0,0 +1,35 @@
+/// The returned layer exports events once the application installs it in its own registry.
+#[cfg(feature = "testing")]
+#[test]
+pub fn returned_layer_bridges_events_to_otlp_logs() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use tracing_subscriber::prelude::*;
+
+    let collector = MockCollector::start();
+    let (otel_manager, layer) = TelemetryBuilder::new("build_layer")
+        .with_endpoint(collector.endpoint())
+        .with_traces_enabled(false)
+        .with_metrics_enabled(false)
+        .without_shutdown_summary()
+        .build_layer()
+        .unwrap();
+    tracing_subscriber::registry()
+        .with(layer)
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .init();
+
+    tracing::warn!("composed");
+    otel_manager.shutdown().unwrap();
+
+    let bodies: Vec<String> = collector
+        .logs()
+        .into_iter()
+        .flat_map(|request| request.resource_logs)
+        .flat_map(|resource| resource.scope_logs)
+        .flat_map(|scope| scope.log_records)
+        .map(|record| format!("{:?}", record.body))
+        .collect();
+    assert_eq!(bodies.len(), 1);
+    assert!(bodies[0].contains("composed"));
+}
@@ -0,0 +1,22 @@
+use observlib::TelemetryBuilder;
+use opentelemetry::trace::Span;
+use std::time::{Duration, Instant};
+
+/// Exporting to an unroutable collector gives up after the connect timeout rather than the
+/// export timeout.
+#[test]
+pub fn unreachable_collector_fails_fast() {
+    let otel_manager = TelemetryBuilder::new("connect-timeout")
+        .with_endpoint("10.255.255.1:4318")
+        .with_connect_timeout(Duration::from_millis(200))
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .without_subscriber()
+        .build()
+        .unwrap();
+
+    observlib::start_root_span("lost").end();
+    let started = Instant::now();
+    let _ = otel_manager.shutdown();
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
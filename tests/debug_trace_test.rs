@@ -0,0 +1,49 @@
+/// With sampling off, only the request carrying the debug header is traced.
+#[cfg(feature = "testing")]
+#[test]
+pub fn debug_trace_header_forces_sampling() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry::trace::{Span, Tracer};
+    use std::collections::HashMap;
+
+    // SAFETY: this file holds a single test, no other thread reads the environment yet.
+    unsafe {
+        std::env::set_var("OTEL_TRACES_SAMPLER", "parentbased_always_off");
+    }
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("debug_trace")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .without_subscriber()
+        .with_debug_trace_header()
+        .build()
+        .unwrap();
+
+    let tracer = observlib::global::tracer("debug_trace");
+    for (name, debug) in [
+        ("plain", None),
+        ("debugged", Some("1")),
+        ("disabled", Some("0")),
+    ] {
+        let mut headers = HashMap::new();
+        if let Some(debug) = debug {
+            headers.insert("x-debug-trace".to_string(), debug.to_string());
+        }
+        let cx = observlib::extract_context(&headers);
+        tracer.start_with_context(name, &cx).end();
+    }
+    otel_manager.shutdown().unwrap();
+
+    let spans: Vec<_> = collector
+        .traces()
+        .into_iter()
+        .flat_map(|request| request.resource_spans)
+        .flat_map(|resource| resource.scope_spans)
+        .flat_map(|scope| scope.spans)
+        .collect();
+    assert_eq!(spans.len(), 1);
+    assert_eq!(spans[0].name, "debugged");
+    assert!(spans[0].attributes.iter().any(|kv| kv.key == "debug.trace"));
+}
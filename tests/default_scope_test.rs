@@ -0,0 +1,32 @@
+/// Spans of observlib's helpers are exported under the observlib scope and its attributes.
+#[cfg(feature = "testing")]
+#[test]
+pub fn default_scope_carries_configured_attributes() {
+    use observlib::testing::MockCollector;
+    use observlib::{KeyValue, TelemetryBuilder};
+    use opentelemetry::trace::Span;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("scoped")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .without_subscriber()
+        .with_scope_attributes([KeyValue::new("library.team", "platform")])
+        .build()
+        .unwrap();
+
+    observlib::start_root_span("helper span").end();
+    otel_manager.shutdown().unwrap();
+
+    let scopes: Vec<_> = collector
+        .traces()
+        .into_iter()
+        .flat_map(|request| request.resource_spans)
+        .flat_map(|resource| resource.scope_spans)
+        .filter_map(|scope_spans| scope_spans.scope)
+        .collect();
+    assert_eq!(scopes.len(), 1);
+    assert_eq!(scopes[0].name, "observlib");
+    assert_eq!(scopes[0].attributes[0].key, "library.team");
+}
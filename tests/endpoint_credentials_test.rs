@@ -0,0 +1,27 @@
+/// Percent-encoded credentials are decoded before being sent.
+#[cfg(feature = "testing")]
+#[test]
+pub fn encoded_credentials_are_decoded() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("credentials")
+        .with_endpoint(format!("http://user:p%40ss@{}", collector.endpoint()))
+        .with_logs_enabled(false)
+        .with_traces_enabled(false)
+        .without_subscriber()
+        .build()
+        .unwrap();
+    observlib::guarded_counter("authenticated").add(1, &[]);
+    otel_manager.shutdown().unwrap();
+
+    let requests = collector.requests();
+    assert!(!requests.is_empty());
+    assert_eq!(requests[0].path, "/v1/metrics");
+    // base64 of user:p@ss
+    assert_eq!(
+        requests[0].header("authorization"),
+        Some("Basic dXNlcjpwQHNz")
+    );
+}
@@ -0,0 +1,24 @@
+/// Short export intervals get spans and metrics out without waiting for shutdown.
+#[cfg(feature = "testing")]
+#[test]
+pub fn export_interval_applies_to_spans_and_metrics() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry::trace::Span;
+    use std::time::Duration;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("export_interval")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .without_subscriber()
+        .with_export_interval(Duration::from_millis(100))
+        .build()
+        .unwrap();
+
+    observlib::start_root_span("early").end();
+    observlib::observe_event("early", &[]);
+    assert!(collector.wait_for("/v1/traces", Duration::from_secs(5)));
+    assert!(collector.wait_for("/v1/metrics", Duration::from_secs(5)));
+    otel_manager.shutdown().unwrap();
+}
@@ -0,0 +1,38 @@
+/// Spans are dealt in turn to one batch processor per export thread, each exporting its own
+/// batch.
+#[cfg(feature = "testing")]
+#[test]
+pub fn spans_are_exported_from_several_threads() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry::trace::Span;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("sharded")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .without_subscriber()
+        .with_export_threads(3)
+        .build()
+        .unwrap();
+
+    for _ in 0..9 {
+        observlib::start_root_span("work").end();
+    }
+    otel_manager.shutdown().unwrap();
+
+    let span_counts: Vec<usize> = collector
+        .traces()
+        .into_iter()
+        .map(|request| {
+            request
+                .resource_spans
+                .into_iter()
+                .flat_map(|resource| resource.scope_spans)
+                .map(|scope| scope.spans.len())
+                .sum()
+        })
+        .collect();
+    assert_eq!(span_counts, [3, 3, 3]);
+}
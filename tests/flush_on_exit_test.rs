@@ -0,0 +1,42 @@
+/// Spans still queued when the process calls `std::process::exit` reach the collector.
+///
+/// The test runs itself again in a child process, which exits without shutting down.
+#[cfg(feature = "testing")]
+#[test]
+pub fn queued_spans_are_flushed_on_exit() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry::trace::Span;
+    use std::process::Command;
+
+    if let Ok(endpoint) = std::env::var("OBSERVLIB_EXIT_ENDPOINT") {
+        let otel_manager = TelemetryBuilder::new("exiting")
+            .with_endpoint(endpoint)
+            .with_logs_enabled(false)
+            .with_metrics_enabled(false)
+            .without_subscriber()
+            .build()
+            .unwrap();
+        otel_manager.flush_on_exit();
+        observlib::start_root_span("last words").end();
+        std::process::exit(1);
+    }
+
+    let collector = MockCollector::start();
+    let status = Command::new(std::env::current_exe().unwrap())
+        .args(["queued_spans_are_flushed_on_exit", "--exact", "--nocapture"])
+        .env("OBSERVLIB_EXIT_ENDPOINT", collector.endpoint())
+        .status()
+        .unwrap();
+    assert_eq!(status.code(), Some(1));
+
+    let span_names: Vec<String> = collector
+        .traces()
+        .into_iter()
+        .flat_map(|request| request.resource_spans)
+        .flat_map(|resource| resource.scope_spans)
+        .flat_map(|scope| scope.spans)
+        .map(|span| span.name)
+        .collect();
+    assert_eq!(span_names, ["last words"]);
+}
@@ -0,0 +1,39 @@
+/// The grpc transport speaks HTTP/2 to the endpoint as given, without an http signal path.
+#[cfg(feature = "grpc")]
+#[tokio::test(flavor = "multi_thread")]
+pub async fn grpc_exports_over_http2() {
+    use observlib::{ExportOptions, TelemetryBuilder, Transport};
+    use opentelemetry::trace::Span;
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    // Only the connection preface is checked, a real collector would answer the request
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = listener.local_addr().unwrap().to_string();
+    let preface = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut preface = [0; 24];
+        stream.read_exact(&mut preface).unwrap();
+        preface
+    });
+
+    let otel_manager = TelemetryBuilder::new("grpc")
+        .with_endpoint(endpoint)
+        .with_transport(Transport::Grpc)
+        .with_export_options(ExportOptions::new().with_timeout(Duration::from_millis(500)))
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .without_subscriber()
+        .build()
+        .unwrap();
+
+    observlib::start_root_span("over grpc").end();
+    let _ = otel_manager.force_flush();
+
+    assert_eq!(
+        &preface.join().unwrap(),
+        b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n"
+    );
+    let _ = otel_manager.shutdown();
+}
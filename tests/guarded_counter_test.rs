@@ -0,0 +1,42 @@
+/// Negative deltas are rejected and left out of the exported sum.
+#[cfg(feature = "testing")]
+#[test]
+pub fn negative_delta_is_ignored() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry_proto::tonic::metrics::v1::metric::Data;
+    use opentelemetry_proto::tonic::metrics::v1::number_data_point::Value;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("guarded")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_traces_enabled(false)
+        .without_subscriber()
+        .build()
+        .unwrap();
+
+    let counter = observlib::guarded_counter("guarded_counter");
+    assert!(counter.add(3, &[]));
+    assert!(!counter.add(-1, &[]));
+    assert!(counter.add(2, &[]));
+    otel_manager.shutdown().unwrap();
+
+    let sums: Vec<i64> = collector
+        .metrics()
+        .into_iter()
+        .flat_map(|request| request.resource_metrics)
+        .flat_map(|resource| resource.scope_metrics)
+        .flat_map(|scope| scope.metrics)
+        .filter(|metric| metric.name == "guarded_counter")
+        .flat_map(|metric| match metric.data {
+            Some(Data::Sum(sum)) => sum.data_points,
+            _ => Vec::new(),
+        })
+        .map(|point| match point.value {
+            Some(Value::AsInt(value)) => value,
+            _ => 0,
+        })
+        .collect();
+    assert_eq!(sums, [5]);
+}
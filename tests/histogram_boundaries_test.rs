@@ -0,0 +1,44 @@
+/// Advised boundaries are exported, also through the metric prefix view, and histograms
+/// without advice keep the SDK's default buckets.
+#[cfg(feature = "testing")]
+#[test]
+pub fn histogram_uses_advisory_boundaries() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry_proto::tonic::metrics::v1::metric::Data;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("histograms")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_traces_enabled(false)
+        .without_subscriber()
+        .with_metric_prefix("app_")
+        .build()
+        .unwrap();
+
+    observlib::histogram("advised", &[1.0, 5.0, 10.0]).record(3.0, &[]);
+    observlib::histogram("app_prefixed", &[0.5]).record(3.0, &[]);
+    observlib::histogram("default", &[]).record(3.0, &[]);
+    otel_manager.shutdown().unwrap();
+
+    let bounds = |name: &str| -> Vec<f64> {
+        collector
+            .metrics()
+            .into_iter()
+            .flat_map(|request| request.resource_metrics)
+            .flat_map(|resource| resource.scope_metrics)
+            .flat_map(|scope| scope.metrics)
+            .filter(|metric| metric.name == name)
+            .find_map(|metric| match metric.data? {
+                Data::Histogram(histogram) => {
+                    Some(histogram.data_points[0].explicit_bounds.clone())
+                }
+                _ => None,
+            })
+            .unwrap()
+    };
+    assert_eq!(bounds("app_advised"), [1.0, 5.0, 10.0]);
+    assert_eq!(bounds("app_prefixed"), [0.5]);
+    assert!(bounds("app_default").len() > 3);
+}
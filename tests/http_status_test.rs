@@ -0,0 +1,18 @@
+use observlib::HttpStatusMapping;
+use opentelemetry::trace::Status;
+
+#[test]
+pub fn only_server_errors_fail_by_default() {
+    let mapping = HttpStatusMapping::default();
+    assert!(mapping.is_error(500));
+    assert!(mapping.is_error(503));
+    assert!(!mapping.is_error(404));
+    assert_eq!(mapping.span_status(200), Status::Unset);
+}
+
+#[test]
+pub fn error_ranges_can_be_customized() {
+    let mapping = HttpStatusMapping::empty().with_error_range(400..=499);
+    assert!(mapping.is_error(404));
+    assert!(!mapping.is_error(500));
+}
@@ -0,0 +1,42 @@
+use observlib::TelemetryBuilder;
+use opentelemetry::trace::{Span, SpanId, TraceId};
+use opentelemetry_sdk::trace::IdGenerator;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Debug, Default)]
+struct SequentialIds {
+    traces: AtomicU64,
+    spans: AtomicU64,
+}
+
+impl IdGenerator for SequentialIds {
+    fn new_trace_id(&self) -> TraceId {
+        TraceId::from(u128::from(self.traces.fetch_add(1, Ordering::Relaxed) + 1))
+    }
+
+    fn new_span_id(&self) -> SpanId {
+        SpanId::from(self.spans.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+}
+
+#[test]
+pub fn custom_id_generator_is_used() {
+    let otel_manager = TelemetryBuilder::new("ids")
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .without_subscriber()
+        .with_id_generator(SequentialIds::default())
+        .build()
+        .unwrap();
+
+    let mut span = observlib::start_root_span("deterministic");
+    assert_eq!(span.span_context().trace_id(), TraceId::from(1u128));
+    assert_eq!(span.span_context().span_id(), SpanId::from(1u64));
+    span.end();
+
+    let sampler = otel_manager.sampler_info().unwrap();
+    assert_eq!(sampler.name, "parentbased_always_on");
+    assert_eq!(sampler.ratio, 1.0);
+
+    otel_manager.shutdown().unwrap();
+}
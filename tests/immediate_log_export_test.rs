@@ -0,0 +1,30 @@
+/// With immediate export, a record reached the collector once the event returned.
+#[cfg(feature = "testing")]
+#[test]
+pub fn logs_are_exported_when_emitted() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("immediate")
+        .with_endpoint(collector.endpoint())
+        .with_metrics_enabled(false)
+        .with_traces_enabled(false)
+        .with_immediate_log_export()
+        .without_shutdown_summary()
+        .build()
+        .unwrap();
+
+    tracing::warn!("right away");
+    let bodies: Vec<String> = collector
+        .logs()
+        .into_iter()
+        .flat_map(|request| request.resource_logs)
+        .flat_map(|resource| resource.scope_logs)
+        .flat_map(|scope| scope.log_records)
+        .map(|record| format!("{:?}", record.body))
+        .collect();
+    assert_eq!(bodies.len(), 1);
+    assert!(bodies[0].contains("right away"));
+    otel_manager.shutdown().unwrap();
+}
@@ -0,0 +1,57 @@
+/// Spans started after an await inside the future are children of its span, `tracing`
+/// events run inside its `tracing` span, and the duration is exported once the future
+/// completes.
+#[cfg(all(feature = "async", feature = "testing"))]
+#[tokio::test(flavor = "multi_thread")]
+async fn instrumented_future_keeps_its_span_across_awaits() {
+    use observlib::testing::MockCollector;
+    use observlib::{KeyValue, TelemetryBuilder};
+    use opentelemetry::trace::Span;
+    use std::time::Duration;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("instrumented")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .build()
+        .unwrap();
+
+    let answer = observlib::instrument_future("load", [KeyValue::new("source", "cache")], async {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        tokio::task::yield_now().await;
+        observlib::span_if(true, "inner", []).unwrap().end();
+        let tracing_span = tracing::Span::current();
+        assert_eq!(
+            tracing_span.metadata().map(|metadata| metadata.name()),
+            Some("instrument_future")
+        );
+        assert!(tracing_span.field("operation").is_some());
+        42
+    })
+    .await;
+    assert_eq!(answer, 42);
+    otel_manager.async_shutdown(None).await.unwrap();
+
+    let spans: Vec<_> = collector
+        .traces()
+        .into_iter()
+        .flat_map(|request| request.resource_spans)
+        .flat_map(|resource| resource.scope_spans)
+        .flat_map(|scope| scope.spans)
+        .collect();
+    let outer = spans.iter().find(|span| span.name == "load").unwrap();
+    let inner = spans.iter().find(|span| span.name == "inner").unwrap();
+    assert_eq!(inner.parent_span_id, outer.span_id);
+    assert_eq!(inner.trace_id, outer.trace_id);
+    assert!(outer.attributes.iter().any(|kv| kv.key == "source"));
+
+    let durations: Vec<_> = collector
+        .metrics()
+        .into_iter()
+        .flat_map(|request| request.resource_metrics)
+        .flat_map(|resource| resource.scope_metrics)
+        .flat_map(|scope| scope.metrics)
+        .filter(|metric| metric.name == "operation.duration")
+        .collect();
+    assert_eq!(durations.len(), 1);
+}
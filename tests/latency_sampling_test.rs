@@ -0,0 +1,58 @@
+/// Fast traces are dropped whole, slow ones are exported with their child spans, including
+/// children ending after their root.
+#[cfg(feature = "testing")]
+#[test]
+pub fn only_slow_traces_are_exported() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry::Context;
+    use opentelemetry::global::BoxedSpan;
+    use opentelemetry::trace::{Span, TraceContextExt, Tracer};
+    use std::time::Duration;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("latency")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .without_subscriber()
+        .with_latency_sampling(Duration::from_millis(50))
+        .build()
+        .unwrap();
+
+    let trace = |root: &'static str, child: &'static str, duration: Duration| {
+        let cx = Context::current_with_span(observlib::start_root_span(root));
+        let mut span: BoxedSpan =
+            observlib::global::tracer("latency").start_with_context(child, &cx);
+        std::thread::sleep(duration);
+        span.end();
+        cx.span().end();
+    };
+    let late_child_trace = |root: &'static str, child: &'static str, duration: Duration| {
+        let cx = Context::current_with_span(observlib::start_root_span(root));
+        let mut span: BoxedSpan =
+            observlib::global::tracer("latency").start_with_context(child, &cx);
+        std::thread::sleep(duration);
+        cx.span().end();
+        span.end();
+    };
+    trace("fast", "fast child", Duration::ZERO);
+    trace("slow", "slow child", Duration::from_millis(60));
+    late_child_trace("fast root", "late fast child", Duration::ZERO);
+    late_child_trace("slow root", "late slow child", Duration::from_millis(60));
+    otel_manager.shutdown().unwrap();
+
+    let mut span_names: Vec<String> = collector
+        .traces()
+        .into_iter()
+        .flat_map(|request| request.resource_spans)
+        .flat_map(|resource| resource.scope_spans)
+        .flat_map(|scope| scope.spans)
+        .map(|span| span.name)
+        .collect();
+    span_names.sort();
+    assert_eq!(
+        span_names,
+        ["late slow child", "slow", "slow child", "slow root"]
+    );
+}
@@ -0,0 +1,46 @@
+/// Events emitted while a span is current are exported with its trace and span ids.
+#[cfg(feature = "testing")]
+#[test]
+pub fn logs_carry_the_current_trace_context() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry::Context;
+    use opentelemetry::trace::TraceContextExt;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("log_correlation")
+        .with_endpoint(collector.endpoint())
+        .with_metrics_enabled(false)
+        .build()
+        .unwrap();
+
+    let cx = Context::current_with_span(observlib::start_root_span("correlated"));
+    let span_context = cx.span().span_context().clone();
+    {
+        let _guard = cx.clone().attach();
+        tracing::warn!("inside the span");
+    }
+    tracing::warn!("outside any span");
+    cx.span().end();
+    otel_manager.shutdown().unwrap();
+
+    let records: Vec<_> = collector
+        .logs()
+        .into_iter()
+        .flat_map(|request| request.resource_logs)
+        .flat_map(|resource| resource.scope_logs)
+        .flat_map(|scope| scope.log_records)
+        .collect();
+    let body = |text: &str| {
+        records
+            .iter()
+            .find(|record| format!("{:?}", record.body).contains(text))
+            .unwrap()
+    };
+
+    let inside = body("inside the span");
+    assert_eq!(inside.trace_id, span_context.trace_id().to_bytes());
+    assert_eq!(inside.span_id, span_context.span_id().to_bytes());
+    let outside = body("outside any span");
+    assert!(outside.trace_id.is_empty());
+}
@@ -0,0 +1,41 @@
+/// Batches past the byte limit are exported in several requests, without losing spans.
+#[cfg(feature = "testing")]
+#[test]
+pub fn large_batches_are_split_by_size() {
+    use observlib::testing::MockCollector;
+    use observlib::{KeyValue, TelemetryBuilder};
+    use opentelemetry::trace::{Span, Tracer};
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("batch_bytes")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .without_subscriber()
+        .with_traces_max_batch_bytes(500)
+        .build()
+        .unwrap();
+
+    let tracer = observlib::global::tracer("batch_bytes");
+    for _ in 0..6 {
+        let mut span = tracer.start("large");
+        span.set_attribute(KeyValue::new("payload", "x".repeat(300)));
+        span.end();
+    }
+    otel_manager.shutdown().unwrap();
+
+    let span_counts: Vec<usize> = collector
+        .traces()
+        .into_iter()
+        .map(|request| {
+            request
+                .resource_spans
+                .iter()
+                .flat_map(|resource| &resource.scope_spans)
+                .map(|scope| scope.spans.len())
+                .sum()
+        })
+        .collect();
+    assert_eq!(span_counts.iter().sum::<usize>(), 6);
+    assert!(span_counts.iter().all(|&count| count == 1));
+}
@@ -0,0 +1,38 @@
+/// Queued spans are exported in requests of at most the max export batch size.
+#[cfg(feature = "testing")]
+#[test]
+pub fn export_requests_respect_the_max_batch_size() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry::trace::Span;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("export_batch_size")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .without_subscriber()
+        .with_traces_max_export_batch_size(2)
+        .build()
+        .unwrap();
+
+    for _ in 0..5 {
+        observlib::start_root_span("batched").end();
+    }
+    otel_manager.shutdown().unwrap();
+
+    let span_counts: Vec<usize> = collector
+        .traces()
+        .into_iter()
+        .map(|request| {
+            request
+                .resource_spans
+                .into_iter()
+                .flat_map(|resource| resource.scope_spans)
+                .map(|scope| scope.spans.len())
+                .sum()
+        })
+        .collect();
+    assert_eq!(span_counts.iter().sum::<usize>(), 5);
+    assert!(span_counts.iter().all(|&count| count <= 2));
+}
@@ -0,0 +1,20 @@
+use observlib::{KeyValue, global};
+
+#[test]
+pub fn metrics_only_instantiation() {
+    let attrs = vec![KeyValue::new("env", "dev")];
+    let otel_manager =
+        observlib::initialize_metrics_only("batch", "127.0.0.1:4318", attrs).unwrap();
+    let counter = global::meter("my meter").u64_counter("my_counter").build();
+    counter.add(1, &[]);
+    otel_manager.shutdown().unwrap();
+}
+
+#[test]
+pub fn metrics_only_reports_a_malformed_endpoint() {
+    let result = observlib::initialize_metrics_only("batch", "not a host:4318", vec![]);
+    assert!(matches!(
+        result,
+        Err(observlib::ObservlibError::MetricExporterBuild(_))
+    ));
+}
@@ -0,0 +1,37 @@
+/// Draining the collector between phases leaves only the later exports to assert on.
+#[cfg(feature = "testing")]
+#[test]
+pub fn drained_requests_are_not_returned_again() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::{ExportTraceServiceRequest, MockCollector};
+    use opentelemetry::trace::Span;
+
+    fn span_names(requests: Vec<ExportTraceServiceRequest>) -> Vec<String> {
+        requests
+            .into_iter()
+            .flat_map(|request| request.resource_spans)
+            .flat_map(|resource| resource.scope_spans)
+            .flat_map(|scope| scope.spans)
+            .map(|span| span.name)
+            .collect()
+    }
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("drained")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .without_subscriber()
+        .build()
+        .unwrap();
+
+    observlib::start_root_span("first phase").end();
+    otel_manager.force_flush().unwrap();
+    assert_eq!(span_names(collector.drain_traces()), ["first phase"]);
+    assert!(collector.traces().is_empty());
+
+    observlib::start_root_span("second phase").end();
+    otel_manager.shutdown().unwrap();
+    assert_eq!(span_names(collector.drain_traces()), ["second phase"]);
+    assert!(collector.drain().is_empty());
+}
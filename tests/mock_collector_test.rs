@@ -0,0 +1,46 @@
+/// Exports real payloads to the in-process collector and checks what it received.
+#[cfg(feature = "testing")]
+#[test]
+pub fn exports_reach_the_mock_collector() {
+    use observlib::testing::MockCollector;
+    use observlib::{KeyValue, TelemetryBuilder, global};
+    use opentelemetry::trace::Span;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("mocked")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .without_subscriber()
+        .with_metric_prefix("mocked_")
+        .build()
+        .unwrap();
+
+    otel_manager.set_dynamic_attribute(KeyValue::new("role", "leader"));
+    observlib::start_root_span("exported").end();
+    let meter = global::meter("mock meter");
+    meter.u64_counter("requests").build().add(1, &[]);
+    meter.u64_counter("mocked_errors").build().add(1, &[]);
+    otel_manager.shutdown().unwrap();
+
+    let spans: Vec<_> = collector
+        .traces()
+        .into_iter()
+        .flat_map(|request| request.resource_spans)
+        .flat_map(|resource| resource.scope_spans)
+        .flat_map(|scope| scope.spans)
+        .collect();
+    assert_eq!(spans.len(), 1);
+    assert_eq!(spans[0].name, "exported");
+    assert!(spans[0].attributes.iter().any(|kv| kv.key == "role"));
+
+    let mut metric_names: Vec<String> = collector
+        .metrics()
+        .into_iter()
+        .flat_map(|request| request.resource_metrics)
+        .flat_map(|resource| resource.scope_metrics)
+        .flat_map(|scope| scope.metrics)
+        .map(|metric| metric.name)
+        .collect();
+    metric_names.sort();
+    assert_eq!(metric_names, ["mocked_errors", "mocked_requests"]);
+}
@@ -0,0 +1,47 @@
+/// One call produces both the counter data point and the span event.
+#[cfg(feature = "testing")]
+#[test]
+pub fn observe_event_counts_and_annotates() {
+    use observlib::testing::MockCollector;
+    use observlib::{KeyValue, TelemetryBuilder};
+    use opentelemetry::Context;
+    use opentelemetry::trace::TraceContextExt;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("observed")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .without_subscriber()
+        .build()
+        .unwrap();
+
+    let cx = Context::current_with_span(observlib::start_root_span("lookup"));
+    {
+        let _guard = cx.clone().attach();
+        observlib::observe_event("cache.miss", &[KeyValue::new("cache", "users")]);
+    }
+    cx.span().end();
+    otel_manager.shutdown().unwrap();
+
+    let events: Vec<_> = collector
+        .traces()
+        .into_iter()
+        .flat_map(|request| request.resource_spans)
+        .flat_map(|resource| resource.scope_spans)
+        .flat_map(|scope| scope.spans)
+        .flat_map(|span| span.events)
+        .collect();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].name, "cache.miss");
+    assert_eq!(events[0].attributes[0].key, "cache");
+
+    let metric_names: Vec<String> = collector
+        .metrics()
+        .into_iter()
+        .flat_map(|request| request.resource_metrics)
+        .flat_map(|resource| resource.scope_metrics)
+        .flat_map(|scope| scope.metrics)
+        .map(|metric| metric.name)
+        .collect();
+    assert_eq!(metric_names, ["cache.miss"]);
+}
@@ -0,0 +1,28 @@
+use observlib::TelemetryBuilder;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The callback runs once, after the providers were created.
+#[test]
+pub fn callback_runs_after_initialization() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counted = calls.clone();
+    let otel_manager = TelemetryBuilder::new("initialized")
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .without_subscriber()
+        .on_initialized(move |otel_manager| {
+            assert!(otel_manager.sampler_info().is_some());
+            counted.fetch_add(1, Ordering::SeqCst);
+        })
+        .build()
+        .unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    let failed = TelemetryBuilder::new("initialized")
+        .with_verbose_targets(["mycrate=loud"])
+        .on_initialized(|_| panic!("called although build failed"))
+        .build();
+    assert!(failed.is_err());
+    otel_manager.shutdown().unwrap();
+}
@@ -0,0 +1,31 @@
+/// By default only warnings and errors are exported, info events stay local.
+#[cfg(feature = "testing")]
+#[test]
+pub fn otel_logs_default_to_warn() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("otel_log_filter")
+        .with_endpoint(collector.endpoint())
+        .with_metrics_enabled(false)
+        .with_traces_enabled(false)
+        .build()
+        .unwrap();
+
+    tracing::info!("stays local");
+    tracing::warn!("is exported");
+    tracing::error!("is exported too");
+    otel_manager.shutdown().unwrap();
+
+    let bodies: Vec<String> = collector
+        .logs()
+        .into_iter()
+        .flat_map(|request| request.resource_logs)
+        .flat_map(|resource| resource.scope_logs)
+        .flat_map(|scope| scope.log_records)
+        .map(|record| format!("{:?}", record.body))
+        .collect();
+    assert_eq!(bodies.len(), 2);
+    assert!(bodies.iter().all(|body| body.contains("is exported")));
+}
@@ -0,0 +1,50 @@
+/// A span over the byte limit is exported without its last events, and a warning names it.
+#[cfg(feature = "testing")]
+#[test]
+pub fn oversized_spans_are_trimmed_with_a_warning() {
+    use observlib::testing::MockCollector;
+    use observlib::{KeyValue, TelemetryBuilder};
+    use opentelemetry::trace::Span;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("oversized")
+        .with_endpoint(collector.endpoint())
+        .with_metrics_enabled(false)
+        .with_otel_log_filter("info")
+        .with_traces_max_batch_bytes(2000)
+        .build()
+        .unwrap();
+
+    let mut span = observlib::start_root_span("bulk_import");
+    for _ in 0..20 {
+        span.add_event("row", vec![KeyValue::new("payload", "x".repeat(300))]);
+    }
+    span.end();
+    otel_manager.shutdown().unwrap();
+
+    let spans: Vec<_> = collector
+        .traces()
+        .into_iter()
+        .flat_map(|request| request.resource_spans)
+        .flat_map(|resource| resource.scope_spans)
+        .flat_map(|scope| scope.spans)
+        .collect();
+    assert_eq!(spans.len(), 1);
+    assert!(!spans[0].events.is_empty());
+    assert_eq!(
+        spans[0].events.len() as u32 + spans[0].dropped_events_count,
+        20
+    );
+    assert!(spans[0].dropped_events_count > 0);
+
+    let warnings: Vec<_> = collector
+        .logs()
+        .into_iter()
+        .flat_map(|request| request.resource_logs)
+        .flat_map(|resource| resource.scope_logs)
+        .flat_map(|scope| scope.log_records)
+        .filter(|record| record.severity_text == "WARN")
+        .collect();
+    assert_eq!(warnings.len(), 1);
+    assert!(format!("{:?}", warnings[0].attributes).contains("bulk_import"));
+}
@@ -0,0 +1,43 @@
+/// The process gauges are exported with plausible values once a sample was taken.
+#[cfg(all(feature = "testing", target_os = "linux"))]
+#[test]
+pub fn process_metrics_are_exported() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry_proto::tonic::metrics::v1::metric::Data;
+    use opentelemetry_proto::tonic::metrics::v1::number_data_point::Value;
+    use std::time::Duration;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("process_metrics")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_traces_enabled(false)
+        .without_subscriber()
+        .with_process_metrics(Duration::from_millis(20))
+        .build()
+        .unwrap();
+
+    std::thread::sleep(Duration::from_millis(100));
+    otel_manager.shutdown().unwrap();
+
+    let gauge = |name: &str| -> f64 {
+        collector
+            .metrics()
+            .into_iter()
+            .flat_map(|request| request.resource_metrics)
+            .flat_map(|resource| resource.scope_metrics)
+            .flat_map(|scope| scope.metrics)
+            .filter(|metric| metric.name == name)
+            .find_map(|metric| match metric.data? {
+                Data::Gauge(gauge) => match gauge.data_points.first()?.value? {
+                    Value::AsDouble(value) => Some(value),
+                    Value::AsInt(value) => Some(value as f64),
+                },
+                _ => None,
+            })
+            .unwrap()
+    };
+    assert!(gauge("process.memory.usage") > 1024.0 * 1024.0);
+    assert!((0.0..=1.0).contains(&gauge("process.cpu.utilization")));
+}
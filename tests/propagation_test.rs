@@ -0,0 +1,27 @@
+#[cfg(feature = "tonic")]
+#[test]
+pub fn grpc_metadata_round_trip() {
+    use opentelemetry::trace::{
+        SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+    };
+    use opentelemetry::{Context, global};
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    let parent = SpanContext::new(
+        TraceId::from(7u128),
+        SpanId::from(9u64),
+        TraceFlags::SAMPLED,
+        true,
+        TraceState::default(),
+    );
+    let _guard = Context::new().with_remote_span_context(parent).attach();
+
+    let outgoing = observlib::inject_grpc_context(tonic::Request::new(())).unwrap();
+    let incoming = tonic::Request::from_parts(outgoing.metadata().clone(), Default::default(), ());
+    let incoming = observlib::extract_grpc_context(incoming).unwrap();
+
+    let cx = observlib::grpc_context(&incoming);
+    assert_eq!(cx.span().span_context().trace_id(), TraceId::from(7u128));
+    assert_eq!(cx.span().span_context().span_id(), SpanId::from(9u64));
+}
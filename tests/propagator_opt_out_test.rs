@@ -0,0 +1,22 @@
+/// The application's propagator is kept when observlib is told not to install its own.
+#[test]
+pub fn application_propagator_is_kept() {
+    use observlib::{TelemetryBuilder, global};
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    let otel_manager = TelemetryBuilder::new("propagator")
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .with_traces_enabled(false)
+        .without_subscriber()
+        .without_propagator()
+        .build()
+        .unwrap();
+
+    let fields: Vec<String> = global::get_text_map_propagator(|propagator| {
+        propagator.fields().map(String::from).collect()
+    });
+    assert_eq!(fields, ["traceparent", "tracestate"]);
+    otel_manager.shutdown().unwrap();
+}
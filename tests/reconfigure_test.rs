@@ -0,0 +1,46 @@
+/// Spans recorded after a reconfiguration go to the new endpoint with the new credentials.
+#[cfg(feature = "testing")]
+#[test]
+pub fn reconfigure_swaps_the_exporters() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry::trace::Span;
+
+    let old = MockCollector::start();
+    let new = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("rotated")
+        .with_endpoint(format!("http://user:old@{}", old.endpoint()))
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .without_subscriber()
+        .build()
+        .unwrap();
+
+    observlib::start_root_span("before").end();
+    otel_manager.force_flush().unwrap();
+    otel_manager
+        .reconfigure(
+            TelemetryBuilder::new("rotated")
+                .with_endpoint(format!("http://user:new@{}", new.endpoint())),
+        )
+        .unwrap();
+    observlib::start_root_span("after").end();
+    otel_manager.shutdown().unwrap();
+
+    let span_names = |collector: &MockCollector| -> Vec<String> {
+        collector
+            .traces()
+            .into_iter()
+            .flat_map(|request| request.resource_spans)
+            .flat_map(|resource| resource.scope_spans)
+            .flat_map(|scope| scope.spans)
+            .map(|span| span.name)
+            .collect()
+    };
+    assert_eq!(span_names(&old), ["before"]);
+    assert_eq!(span_names(&new), ["after"]);
+    assert_eq!(
+        new.requests()[0].header("authorization"),
+        Some("Basic dXNlcjpuZXc=")
+    );
+}
@@ -0,0 +1,37 @@
+/// Durations are recorded in seconds.
+#[cfg(feature = "testing")]
+#[test]
+pub fn durations_are_recorded_in_seconds() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry_proto::tonic::metrics::v1::metric::Data;
+    use std::time::Duration;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("durations")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_traces_enabled(false)
+        .without_subscriber()
+        .build()
+        .unwrap();
+
+    let histogram = observlib::histogram("job.duration", &[]);
+    observlib::record_duration(&histogram, Duration::from_millis(1500), &[]);
+    observlib::record_duration(&histogram, Duration::from_micros(250), &[]);
+    otel_manager.shutdown().unwrap();
+
+    let sum = collector
+        .metrics()
+        .into_iter()
+        .flat_map(|request| request.resource_metrics)
+        .flat_map(|resource| resource.scope_metrics)
+        .flat_map(|scope| scope.metrics)
+        .filter(|metric| metric.name == "job.duration")
+        .find_map(|metric| match metric.data? {
+            Data::Histogram(histogram) => histogram.data_points[0].sum,
+            _ => None,
+        })
+        .unwrap();
+    assert!((sum - 1.50025).abs() < 1e-9);
+}
@@ -0,0 +1,55 @@
+/// Requests and errors are counted under the shared names and attributes.
+#[cfg(feature = "testing")]
+#[test]
+pub fn requests_and_errors_share_their_attributes() {
+    use observlib::testing::MockCollector;
+    use observlib::{KeyValue, TelemetryBuilder};
+    use opentelemetry_proto::tonic::metrics::v1::metric::Data;
+    use opentelemetry_proto::tonic::metrics::v1::number_data_point::Value;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("red")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_traces_enabled(false)
+        .without_subscriber()
+        .build()
+        .unwrap();
+
+    let route = [KeyValue::new("http.route", "/users")];
+    observlib::record_request(&route);
+    observlib::record_request(&route);
+    observlib::record_error_metric(&route);
+    otel_manager.shutdown().unwrap();
+
+    let mut counts: Vec<(String, i64, String)> = collector
+        .metrics()
+        .into_iter()
+        .flat_map(|request| request.resource_metrics)
+        .flat_map(|resource| resource.scope_metrics)
+        .flat_map(|scope| scope.metrics)
+        .flat_map(|metric| {
+            let Some(Data::Sum(sum)) = metric.data else {
+                return Vec::new();
+            };
+            sum.data_points
+                .into_iter()
+                .map(|point| {
+                    let value = match point.value {
+                        Some(Value::AsInt(value)) => value,
+                        _ => 0,
+                    };
+                    (metric.name.clone(), value, point.attributes[0].key.clone())
+                })
+                .collect()
+        })
+        .collect();
+    counts.sort();
+    assert_eq!(
+        counts,
+        [
+            ("errors_total".to_string(), 1, "http.route".to_string()),
+            ("requests_total".to_string(), 2, "http.route".to_string()),
+        ]
+    );
+}
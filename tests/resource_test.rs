@@ -0,0 +1,86 @@
+use observlib::{KeyValue, Resource, TelemetryBuilder};
+use opentelemetry::{Key, Value};
+
+#[test]
+pub fn typed_resource_attributes_are_preserved() {
+    let resource = TelemetryBuilder::new("blah")
+        .with_attribute(KeyValue::new("feature.enabled", true))
+        .with_attribute(KeyValue::new("replicas", 3))
+        .with_attribute(KeyValue::new("ratio", 0.5))
+        .build_resource();
+
+    assert_eq!(
+        resource.get(&Key::new("feature.enabled")),
+        Some(Value::Bool(true))
+    );
+    assert_eq!(resource.get(&Key::new("replicas")), Some(Value::I64(3)));
+    assert_eq!(resource.get(&Key::new("ratio")), Some(Value::F64(0.5)));
+    assert!(resource.get(&Key::new("service.instance.id")).is_some());
+}
+
+#[test]
+pub fn prebuilt_resource_is_used_as_is() {
+    let prebuilt = Resource::builder_empty()
+        .with_attribute(KeyValue::new("service.name", "custom"))
+        .build();
+    let resource = TelemetryBuilder::new("blah")
+        .with_resource(prebuilt)
+        .build_resource();
+
+    assert_eq!(
+        resource.get(&Key::new("service.name")),
+        Some(Value::from("custom"))
+    );
+    assert_eq!(resource.get(&Key::new("service.instance.id")), None);
+}
+
+#[test]
+pub fn resources_are_built_per_builder() {
+    let first = TelemetryBuilder::new("first").build_resource();
+    let second = TelemetryBuilder::new("second").build_resource();
+
+    assert_eq!(
+        first.get(&Key::new("service.name")),
+        Some(Value::from("first"))
+    );
+    assert_eq!(
+        second.get(&Key::new("service.name")),
+        Some(Value::from("second"))
+    );
+}
+
+#[test]
+pub fn telemetry_sdk_attributes_can_be_overridden() {
+    let default = TelemetryBuilder::new("blah").build_resource();
+    assert_eq!(
+        default.get(&Key::new("telemetry.sdk.name")),
+        Some(Value::from("opentelemetry"))
+    );
+
+    let resource = TelemetryBuilder::new("blah")
+        .with_telemetry_sdk("acme-telemetry", "2.1.0")
+        .build_resource();
+    assert_eq!(
+        resource.get(&Key::new("telemetry.sdk.name")),
+        Some(Value::from("acme-telemetry"))
+    );
+    assert_eq!(
+        resource.get(&Key::new("telemetry.sdk.version")),
+        Some(Value::from("2.1.0"))
+    );
+    assert_eq!(
+        resource.get(&Key::new("telemetry.sdk.language")),
+        Some(Value::from("rust"))
+    );
+}
+
+#[test]
+pub fn namespace_is_a_resource_attribute() {
+    let resource = TelemetryBuilder::new("blah")
+        .with_namespace("payments")
+        .build_resource();
+    assert_eq!(
+        resource.get(&Key::new("service.namespace")),
+        Some(Value::from("payments"))
+    );
+}
@@ -0,0 +1,39 @@
+/// Telemetry built inside a tokio runtime must neither panic nor hang when:
+/// - exporters are replaced or the manager is dropped on a runtime thread
+/// - the runtime is gone before the manager is shut down and dropped
+#[cfg(feature = "async")]
+#[test]
+fn manager_survives_its_runtime() {
+    use observlib::TelemetryBuilder;
+    use opentelemetry::trace::Span;
+    use std::time::Duration;
+
+    let build = || {
+        let otel_manager = TelemetryBuilder::new("runtime-test")
+            .with_logs_enabled(false)
+            .without_subscriber()
+            .build()
+            .unwrap();
+        observlib::start_root_span("queued").end();
+        otel_manager
+    };
+
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let otel_manager = build();
+        otel_manager
+            .reconfigure(TelemetryBuilder::new("runtime-test"))
+            .unwrap();
+        drop(otel_manager);
+    });
+
+    let otel_manager = runtime.block_on(async { build() });
+    drop(runtime);
+    let result =
+        futures_executor::block_on(otel_manager.async_shutdown(Some(Duration::from_secs(5))));
+    assert!(
+        result.is_ok(),
+        "shutdown after the runtime is gone: {result:?}"
+    );
+    drop(otel_manager);
+}
@@ -0,0 +1,20 @@
+use observlib::{ObservlibError, TelemetryBuilder};
+
+/// The default fmt layer follows RUST_LOG, a malformed value is an error rather than a panic.
+#[test]
+pub fn malformed_rust_log_is_rejected() {
+    // SAFETY: this file holds a single test, no other thread reads the environment yet.
+    unsafe {
+        std::env::set_var("RUST_LOG", "mycrate=loud");
+    }
+    let result = TelemetryBuilder::new("rust_log").build();
+    assert!(matches!(result, Err(ObservlibError::InvalidFilter(_))));
+
+    let result = TelemetryBuilder::new("rust_log")
+        .with_fmt_layer(observlib::FmtLayerConfig::new("info"))
+        .with_logs_enabled(false)
+        .with_traces_enabled(false)
+        .with_metrics_enabled(false)
+        .build();
+    assert!(result.is_ok());
+}
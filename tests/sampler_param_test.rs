@@ -0,0 +1,54 @@
+/// With a ratio sampler, sampled root spans carry the ratio while their children do not.
+#[cfg(feature = "testing")]
+#[test]
+pub fn ratio_sampled_roots_carry_sampler_param() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry::Context;
+    use opentelemetry::global::BoxedSpan;
+    use opentelemetry::trace::{Span, TraceContextExt, Tracer};
+
+    // SAFETY: this file holds a single test, no other thread reads the environment yet.
+    unsafe {
+        std::env::set_var("OTEL_TRACES_SAMPLER", "parentbased_traceidratio");
+        std::env::set_var("OTEL_TRACES_SAMPLER_ARG", "1.0");
+    }
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("sampler_param")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .without_subscriber()
+        .build()
+        .unwrap();
+    assert_eq!(
+        otel_manager.sampler_info().unwrap().name,
+        "parentbased_traceidratio"
+    );
+
+    let cx = Context::current_with_span(observlib::start_root_span("root"));
+    let mut child: BoxedSpan =
+        observlib::global::tracer("sampler_param").start_with_context("child", &cx);
+    child.end();
+    cx.span().end();
+    otel_manager.shutdown().unwrap();
+
+    let spans: Vec<_> = collector
+        .traces()
+        .into_iter()
+        .flat_map(|request| request.resource_spans)
+        .flat_map(|resource| resource.scope_spans)
+        .flat_map(|scope| scope.spans)
+        .collect();
+    let has_param = |name: &str| {
+        spans
+            .iter()
+            .find(|span| span.name == name)
+            .unwrap()
+            .attributes
+            .iter()
+            .any(|kv| kv.key == "sampler.param")
+    };
+    assert!(has_param("root"));
+    assert!(!has_param("child"));
+}
@@ -0,0 +1,49 @@
+/// The builder's ratio replaces the default sampler, children follow their parent when
+/// sampling is parent based.
+#[cfg(feature = "testing")]
+#[test]
+pub fn builder_ratio_drops_root_spans() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry::trace::{
+        Span, SpanContext, TraceContextExt, TraceFlags, TraceId, TraceState, Tracer,
+    };
+    use opentelemetry::{Context, trace::SpanId};
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("sampling_ratio")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .without_subscriber()
+        .with_sampling_ratio(0.0)
+        .with_parent_based_sampling(true)
+        .build()
+        .unwrap();
+    let sampler = otel_manager.sampler_info().unwrap();
+    assert_eq!(sampler.name, "parentbased_traceidratio");
+    assert_eq!(sampler.ratio, 0.0);
+
+    observlib::start_root_span("dropped").end();
+    let remote = Context::new().with_remote_span_context(SpanContext::new(
+        TraceId::from(1),
+        SpanId::from(1),
+        TraceFlags::SAMPLED,
+        true,
+        TraceState::default(),
+    ));
+    observlib::global::tracer("sampling")
+        .start_with_context("kept", &remote)
+        .end();
+    otel_manager.shutdown().unwrap();
+
+    let names: Vec<String> = collector
+        .traces()
+        .into_iter()
+        .flat_map(|request| request.resource_spans)
+        .flat_map(|resource| resource.scope_spans)
+        .flat_map(|scope| scope.spans)
+        .map(|span| span.name)
+        .collect();
+    assert_eq!(names, ["kept"]);
+}
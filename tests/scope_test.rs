@@ -0,0 +1,32 @@
+/// Spans of a scoped tracer are exported under a scope carrying its attributes.
+#[cfg(feature = "testing")]
+#[test]
+pub fn scope_attributes_are_exported() {
+    use observlib::testing::MockCollector;
+    use observlib::{KeyValue, ScopedTelemetry, TelemetryBuilder};
+    use opentelemetry::trace::{Span, Tracer};
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("monolith")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .without_subscriber()
+        .build()
+        .unwrap();
+
+    let billing = ScopedTelemetry::new("billing", [KeyValue::new("module.name", "billing")]);
+    billing.tracer().start("charge").end();
+    otel_manager.shutdown().unwrap();
+
+    let scopes: Vec<_> = collector
+        .traces()
+        .into_iter()
+        .flat_map(|request| request.resource_spans)
+        .flat_map(|resource| resource.scope_spans)
+        .filter_map(|scope_spans| scope_spans.scope)
+        .collect();
+    assert_eq!(scopes.len(), 1);
+    assert_eq!(scopes[0].name, "billing");
+    assert_eq!(scopes[0].attributes[0].key, "module.name");
+}
@@ -0,0 +1,45 @@
+/// Misspelled convention keys are reported once, known and custom keys are not.
+#[cfg(feature = "testing")]
+#[test]
+pub fn misspelled_convention_keys_are_reported() {
+    use observlib::testing::MockCollector;
+    use observlib::{KeyValue, TelemetryBuilder};
+    use opentelemetry::trace::Span;
+    use opentelemetry_proto::tonic::common::v1::any_value::Value;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("validated")
+        .with_endpoint(collector.endpoint())
+        .with_metrics_enabled(false)
+        .with_attribute(KeyValue::new("service.versoin", "1.0"))
+        .with_semconv_validation(["http.tenant"])
+        .build()
+        .unwrap();
+
+    for _ in 0..2 {
+        let mut span = observlib::start_root_span("request");
+        span.set_attribute(KeyValue::new("http.statuscode", 200));
+        span.set_attribute(KeyValue::new("http.route", "/users"));
+        span.set_attribute(KeyValue::new("http.request.header.accept", "*/*"));
+        span.set_attribute(KeyValue::new("http.tenant", "acme"));
+        span.set_attribute(KeyValue::new("myapp.cache", "hit"));
+        span.end();
+    }
+    otel_manager.shutdown().unwrap();
+
+    let mut reported: Vec<String> = collector
+        .logs()
+        .into_iter()
+        .flat_map(|request| request.resource_logs)
+        .flat_map(|resource| resource.scope_logs)
+        .flat_map(|scope| scope.log_records)
+        .flat_map(|record| record.attributes)
+        .filter(|attribute| attribute.key == "key")
+        .filter_map(|attribute| match attribute.value?.value? {
+            Value::StringValue(key) => Some(key),
+            _ => None,
+        })
+        .collect();
+    reported.sort();
+    assert_eq!(reported, ["http.statuscode", "service.versoin"]);
+}
@@ -0,0 +1,36 @@
+/// `without_shutdown_summary` keeps shutdown silent.
+#[cfg(feature = "testing")]
+#[test]
+pub fn shutdown_summary_can_be_suppressed() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry::trace::Span;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("summary")
+        .with_endpoint(collector.endpoint())
+        .with_metrics_enabled(false)
+        .with_otel_log_filter("info")
+        .without_shutdown_summary()
+        .build()
+        .unwrap();
+
+    observlib::start_root_span("quiet").end();
+    tracing::info!("still exported");
+    otel_manager.shutdown().unwrap();
+
+    let bodies: Vec<String> = collector
+        .logs()
+        .into_iter()
+        .flat_map(|request| request.resource_logs)
+        .flat_map(|resource| resource.scope_logs)
+        .flat_map(|scope| scope.log_records)
+        .map(|record| format!("{:?}", record.body))
+        .collect();
+    assert!(bodies.iter().any(|body| body.contains("still exported")));
+    assert!(
+        !bodies
+            .iter()
+            .any(|body| body.contains("telemetry shutting down"))
+    );
+}
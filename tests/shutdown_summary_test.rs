@@ -0,0 +1,41 @@
+/// Shutdown logs what was exported before the logger stops.
+#[cfg(feature = "testing")]
+#[test]
+pub fn shutdown_logs_a_summary() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry::trace::Span;
+    use opentelemetry_proto::tonic::common::v1::any_value::Value;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("summary")
+        .with_endpoint(collector.endpoint())
+        .with_metrics_enabled(false)
+        .with_otel_log_filter("info")
+        .build()
+        .unwrap();
+
+    observlib::start_root_span("first").end();
+    observlib::start_root_span("second").end();
+    otel_manager.shutdown().unwrap();
+
+    let summaries: Vec<_> = collector
+        .logs()
+        .into_iter()
+        .flat_map(|request| request.resource_logs)
+        .flat_map(|resource| resource.scope_logs)
+        .flat_map(|scope| scope.log_records)
+        .filter(|record| format!("{:?}", record.body).contains("telemetry shutting down"))
+        .collect();
+    assert_eq!(summaries.len(), 1);
+    let attribute = |key: &str| {
+        summaries[0]
+            .attributes
+            .iter()
+            .find(|attribute| attribute.key == key)
+            .and_then(|attribute| attribute.value.clone()?.value)
+    };
+    assert_eq!(attribute("spans_exported"), Some(Value::IntValue(2)));
+    assert_eq!(attribute("failed_exports"), Some(Value::IntValue(0)));
+    assert!(matches!(attribute("uptime_secs"), Some(Value::DoubleValue(uptime)) if uptime > 0.0));
+}
@@ -0,0 +1,24 @@
+/// A span left open with a shutdown grace period makes shutdown wait for the whole
+/// grace period, which stands in for a slow provider.
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn async_shutdown_times_out_on_slow_shutdown() {
+    use observlib::{ObservlibError, TelemetryBuilder};
+    use std::time::Duration;
+
+    let otel_manager = TelemetryBuilder::new("slow-shutdown")
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .without_subscriber()
+        .with_shutdown_grace_period(Duration::from_secs(5))
+        .build()
+        .unwrap();
+
+    let _open_span = observlib::start_root_span("never ends");
+
+    let result = otel_manager
+        .async_shutdown(Some(Duration::from_millis(100)))
+        .await;
+
+    assert!(matches!(result, Err(ObservlibError::ShutdownTimeout)));
+}
@@ -0,0 +1,42 @@
+/// A signal with its own endpoint is exported there, the others to the shared endpoint.
+#[cfg(feature = "testing")]
+#[test]
+pub fn signals_go_to_their_own_endpoint() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry::trace::Span;
+
+    let shared = MockCollector::start();
+    let traces = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("signal_endpoint")
+        .with_endpoint(format!("http://shared:secret@{}", shared.endpoint()))
+        .with_traces_endpoint(format!("http://tempo:token@{}", traces.endpoint()))
+        .with_metrics_enabled(false)
+        .with_otel_log_filter("info")
+        .without_shutdown_summary()
+        .build()
+        .unwrap();
+
+    observlib::start_root_span("elsewhere").end();
+    tracing::info!("shared");
+    otel_manager.shutdown().unwrap();
+
+    assert!(shared.traces().is_empty());
+    assert!(!shared.logs().is_empty());
+    assert!(traces.logs().is_empty());
+    assert_eq!(traces.traces().len(), 1);
+
+    let authorization = |collector: &MockCollector| {
+        collector.requests()[0]
+            .header("authorization")
+            .map(str::to_string)
+    };
+    assert_eq!(
+        authorization(&traces).as_deref(),
+        Some("Basic dGVtcG86dG9rZW4=")
+    );
+    assert_eq!(
+        authorization(&shared).as_deref(),
+        Some("Basic c2hhcmVkOnNlY3JldA==")
+    );
+}
@@ -0,0 +1,52 @@
+/// Metric-only resource attributes reach the exported metrics but not the spans.
+#[cfg(feature = "testing")]
+#[test]
+pub fn signal_resource_attributes_stay_on_their_signal() {
+    use observlib::testing::MockCollector;
+    use observlib::{KeyValue, TelemetryBuilder, global};
+    use opentelemetry::trace::Span;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("signal_resource")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .without_subscriber()
+        .with_metrics_resource_attributes([KeyValue::new("cost_center", "cc-42")])
+        .build()
+        .unwrap();
+
+    observlib::start_root_span("untagged").end();
+    global::meter("signal_resource")
+        .u64_counter("tagged")
+        .build()
+        .add(1, &[]);
+    otel_manager.shutdown().unwrap();
+
+    let metric_resources: Vec<_> = collector
+        .metrics()
+        .into_iter()
+        .flat_map(|request| request.resource_metrics)
+        .filter_map(|resource| resource.resource)
+        .collect();
+    assert!(!metric_resources.is_empty());
+    assert!(
+        metric_resources
+            .iter()
+            .all(|resource| resource.attributes.iter().any(|kv| kv.key == "cost_center"))
+    );
+
+    let span_resources: Vec<_> = collector
+        .traces()
+        .into_iter()
+        .flat_map(|request| request.resource_spans)
+        .filter_map(|resource| resource.resource)
+        .collect();
+    assert!(!span_resources.is_empty());
+    assert!(span_resources.iter().all(|resource| {
+        !resource.attributes.iter().any(|kv| kv.key == "cost_center")
+            && resource
+                .attributes
+                .iter()
+                .any(|kv| kv.key == "service.name")
+    }));
+}
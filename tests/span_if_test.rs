@@ -0,0 +1,45 @@
+/// Only the spans whose predicate held reach the collector, with their attributes, and
+/// computed names are only computed for them.
+#[cfg(feature = "testing")]
+#[test]
+pub fn span_if_skips_spans_when_predicate_is_false() {
+    use observlib::testing::MockCollector;
+    use observlib::{KeyValue, TelemetryBuilder};
+    use opentelemetry::trace::Span;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("span_if")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .without_subscriber()
+        .build()
+        .unwrap();
+
+    assert!(observlib::span_if(false, "skipped", [KeyValue::new("hot", true)]).is_none());
+    let skipped_name = || -> String { panic!("name computed for a skipped span") };
+    assert!(observlib::span_if(false, skipped_name, []).is_none());
+    let mut span = observlib::span_if(true, "created", [KeyValue::new("hot", true)]).unwrap();
+    span.end();
+    let target = "users";
+    let mut span = observlib::span_if(true, || format!("load {target}"), []).unwrap();
+    span.end();
+    otel_manager.shutdown().unwrap();
+
+    let spans: Vec<_> = collector
+        .traces()
+        .into_iter()
+        .flat_map(|request| request.resource_spans)
+        .flat_map(|resource| resource.scope_spans)
+        .flat_map(|scope| scope.spans)
+        .collect();
+    assert_eq!(spans.len(), 2);
+    assert_eq!(spans[0].name, "created");
+    assert_eq!(spans[1].name, "load users");
+    assert!(
+        spans[0]
+            .attributes
+            .iter()
+            .any(|attribute| attribute.key == "hot")
+    );
+}
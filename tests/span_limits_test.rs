@@ -0,0 +1,53 @@
+/// Oversized values are truncated while attributes past the count are dropped.
+#[cfg(feature = "testing")]
+#[test]
+pub fn span_limits_truncate_and_drop_attributes() {
+    use observlib::testing::MockCollector;
+    use observlib::{KeyValue, SpanLimits, TelemetryBuilder};
+    use opentelemetry::trace::Span;
+    use opentelemetry_proto::tonic::common::v1::any_value::Value;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("limited")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .without_subscriber()
+        .with_span_limits(
+            SpanLimits::new()
+                .with_max_attributes_per_span(2)
+                .with_max_attribute_value_length(4),
+        )
+        .build()
+        .unwrap();
+
+    let mut span = observlib::start_root_span("limited");
+    span.set_attribute(KeyValue::new("payload", "héllo wörld"));
+    span.set_attribute(KeyValue::new("short", "ok"));
+    span.set_attribute(KeyValue::new("dropped", "value"));
+    span.end();
+    otel_manager.shutdown().unwrap();
+
+    let attributes: Vec<(String, Option<Value>)> = collector
+        .traces()
+        .into_iter()
+        .flat_map(|request| request.resource_spans)
+        .flat_map(|resource| resource.scope_spans)
+        .flat_map(|scope| scope.spans)
+        .flat_map(|span| span.attributes)
+        .map(|kv| (kv.key, kv.value.and_then(|value| value.value)))
+        .collect();
+    assert_eq!(
+        attributes,
+        [
+            (
+                "payload".to_string(),
+                Some(Value::StringValue("héll".to_string()))
+            ),
+            (
+                "short".to_string(),
+                Some(Value::StringValue("ok".to_string()))
+            ),
+        ]
+    );
+}
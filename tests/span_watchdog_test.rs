@@ -0,0 +1,37 @@
+/// A span left open past the threshold is reported once, a quick one is not.
+#[cfg(feature = "testing")]
+#[test]
+pub fn spans_open_too_long_are_reported() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry::trace::Span;
+    use std::time::Duration;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("watchdog")
+        .with_endpoint(collector.endpoint())
+        .with_metrics_enabled(false)
+        .with_span_watchdog(Duration::from_millis(50))
+        .build()
+        .unwrap();
+
+    observlib::start_root_span("quick").end();
+    let mut leaked = observlib::start_root_span("leaked");
+    std::thread::sleep(Duration::from_millis(200));
+    leaked.end();
+    otel_manager.shutdown().unwrap();
+
+    let reported: Vec<String> = collector
+        .logs()
+        .into_iter()
+        .flat_map(|request| request.resource_logs)
+        .flat_map(|resource| resource.scope_logs)
+        .flat_map(|scope| scope.log_records)
+        .filter(|record| format!("{:?}", record.body).contains("watchdog threshold"))
+        .flat_map(|record| record.attributes)
+        .filter(|attribute| attribute.key == "span")
+        .map(|attribute| format!("{:?}", attribute.value))
+        .collect();
+    assert_eq!(reported.len(), 1);
+    assert!(reported[0].contains("leaked"));
+}
@@ -0,0 +1,47 @@
+/// A span started on a spawned thread is a child of the span current when spawning.
+#[cfg(feature = "testing")]
+#[test]
+pub fn spawned_thread_keeps_the_parent_span() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry::Context;
+    use opentelemetry::trace::{Span, TraceContextExt, Tracer};
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("spawned")
+        .with_endpoint(collector.endpoint())
+        .with_logs_enabled(false)
+        .with_metrics_enabled(false)
+        .without_subscriber()
+        .build()
+        .unwrap();
+
+    let cx = Context::current_with_span(observlib::start_root_span("parent"));
+    {
+        let _guard = cx.clone().attach();
+        observlib::spawn_with_context(|| {
+            observlib::global::tracer("spawned").start("child").end();
+        })
+        .join()
+        .unwrap();
+    }
+    std::thread::spawn(|| {
+        observlib::global::tracer("spawned").start("orphan").end();
+    })
+    .join()
+    .unwrap();
+    cx.span().end();
+    otel_manager.shutdown().unwrap();
+
+    let spans: Vec<_> = collector
+        .traces()
+        .into_iter()
+        .flat_map(|request| request.resource_spans)
+        .flat_map(|resource| resource.scope_spans)
+        .flat_map(|scope| scope.spans)
+        .collect();
+    let span = |name: &str| spans.iter().find(|span| span.name == name).unwrap();
+    assert_eq!(span("child").parent_span_id, span("parent").span_id);
+    assert_eq!(span("child").trace_id, span("parent").trace_id);
+    assert!(span("orphan").parent_span_id.is_empty());
+}
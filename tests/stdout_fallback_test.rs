@@ -0,0 +1,40 @@
+/// Spans whose export fails are printed once the failure threshold is reached.
+///
+/// The test runs itself again in a child process to capture what it prints.
+#[test]
+pub fn failed_exports_fall_back_to_stdout() {
+    use observlib::TelemetryBuilder;
+    use opentelemetry::trace::Span;
+    use std::process::Command;
+
+    if std::env::var("OBSERVLIB_FALLBACK_CHILD").is_ok() {
+        let otel_manager = TelemetryBuilder::new("stranded")
+            .with_endpoint("127.0.0.1:1")
+            .with_logs_enabled(false)
+            .with_metrics_enabled(false)
+            .without_subscriber()
+            .with_stdout_fallback(0)
+            .build()
+            .unwrap();
+        observlib::start_root_span("stranded").end();
+        let _ = otel_manager.shutdown();
+        return;
+    }
+
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args([
+            "failed_exports_fall_back_to_stdout",
+            "--exact",
+            "--nocapture",
+        ])
+        .env("OBSERVLIB_FALLBACK_CHILD", "1")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout
+            .lines()
+            .any(|line| line.contains("span trace_id=") && line.contains("name=\"stranded\"")),
+        "no fallback line in:\n{stdout}"
+    );
+}
@@ -0,0 +1,16 @@
+use observlib::{ObservlibError, SubscriberPolicy, TelemetryBuilder};
+use tracing_subscriber::util::SubscriberInitExt;
+
+#[test]
+pub fn existing_subscriber_policy() {
+    tracing_subscriber::registry().init();
+
+    let result = TelemetryBuilder::new("blah").build();
+    assert!(matches!(result, Err(ObservlibError::SubscriberInit(_))));
+
+    let otel_manager = TelemetryBuilder::new("blah")
+        .with_subscriber_policy(SubscriberPolicy::BestEffort)
+        .build()
+        .unwrap();
+    otel_manager.shutdown().unwrap();
+}
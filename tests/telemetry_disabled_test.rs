@@ -0,0 +1,23 @@
+/// Without the enabling environment variable nothing reaches the collector.
+#[cfg(feature = "testing")]
+#[test]
+pub fn telemetry_stays_off_without_the_env_flag() {
+    use observlib::TelemetryBuilder;
+    use observlib::testing::MockCollector;
+    use opentelemetry::trace::Span;
+
+    let collector = MockCollector::start();
+    let otel_manager = TelemetryBuilder::new("disabled")
+        .with_endpoint(collector.endpoint())
+        .with_telemetry_enabled_env("OBSERVLIB_TEST_TELEMETRY_ENABLED")
+        .build()
+        .unwrap();
+
+    observlib::start_root_span("ignored").end();
+    observlib::observe_event("ignored", &[]);
+    tracing::error!("ignored");
+    assert!(otel_manager.sampler_info().is_none());
+    otel_manager.shutdown().unwrap();
+
+    assert!(collector.requests().is_empty());
+}
@@ -0,0 +1,60 @@
+/// The same code runs against the mock, which records the calls, and the real manager,
+/// which exports them.
+#[cfg(feature = "testing")]
+#[test]
+pub fn telemetry_trait_is_implemented_by_mock_and_manager() {
+    use observlib::testing::{MockCollector, MockTelemetry, TelemetryCall};
+    use observlib::{KeyValue, OtelManager, Telemetry, TelemetryBuilder};
+    use opentelemetry::trace::Span;
+
+    // Application code written against the trait
+    fn handle_request(telemetry: &dyn Telemetry, user: &str) {
+        let mut span = telemetry.span(format!("load {user}").into(), &[]);
+        telemetry.counter("requests".into(), 1, &[KeyValue::new("route", "/users")]);
+        span.end();
+    }
+
+    let mock = MockTelemetry::init(TelemetryBuilder::new("mocked")).unwrap();
+    handle_request(&mock, "alice");
+    handle_request(&mock, "bob");
+    mock.shutdown().unwrap();
+    assert_eq!(
+        mock.calls()[0],
+        TelemetryCall::Init {
+            service_name: "mocked"
+        }
+    );
+    assert_eq!(mock.span_names(), ["load alice", "load bob"]);
+    assert_eq!(mock.counter_total("requests"), 2);
+    assert!(mock.is_shut_down());
+
+    let collector = MockCollector::start();
+    let otel_manager = OtelManager::init(
+        TelemetryBuilder::new("real")
+            .with_endpoint(collector.endpoint())
+            .with_logs_enabled(false)
+            .without_subscriber(),
+    )
+    .unwrap();
+    handle_request(&otel_manager, "carol");
+    Telemetry::shutdown(&otel_manager).unwrap();
+
+    let spans: Vec<String> = collector
+        .traces()
+        .into_iter()
+        .flat_map(|request| request.resource_spans)
+        .flat_map(|resource| resource.scope_spans)
+        .flat_map(|scope| scope.spans)
+        .map(|span| span.name)
+        .collect();
+    assert_eq!(spans, ["load carol"]);
+    assert!(
+        collector
+            .metrics()
+            .into_iter()
+            .flat_map(|request| request.resource_metrics)
+            .flat_map(|resource| resource.scope_metrics)
+            .flat_map(|scope| scope.metrics)
+            .any(|metric| metric.name == "requests")
+    );
+}
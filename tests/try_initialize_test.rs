@@ -0,0 +1,8 @@
+use observlib::ObservlibError;
+
+/// An endpoint exporters cannot be built for is reported rather than panicking.
+#[test]
+pub fn malformed_endpoint_is_an_error() {
+    let result = observlib::try_initialize_telemetry("fallible", "not a host:4318", vec![]);
+    assert!(matches!(result, Err(ObservlibError::LogExporterBuild(_))));
+}